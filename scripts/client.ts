@@ -47,7 +47,10 @@ interface MarketData {
   totalVotes: BN;
   emojiIds: number[];
   emojiVotes: BN[];
-  winner: number | null;
+  tiePolicy: number;
+  payoutBps: number[];
+  winners: number[];
+  winnerPayouts: BN[];
   platformFeeTaken: BN;
   creatorFeeTaken: BN;
 }
@@ -76,6 +79,16 @@ class PDAHelper {
     );
   }
 
+  /**
+   * Dérive l'adresse PDA de la Treasury (frais de plateforme)
+   */
+  static getTreasuryPDA(programId: PublicKey = PROGRAM_ID): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury")],
+      programId
+    );
+  }
+
   /**
    * Dérive l'adresse PDA d'un Market
    */
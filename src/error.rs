@@ -55,4 +55,112 @@ pub enum ErrorCode {
 
     #[msg("Image URL too long")]
     ImageUrlTooLong,
+
+    #[msg("Invalid admin address")]
+    InvalidAdmin,
+
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+
+    #[msg("Caller is not the pending admin")]
+    NotPendingAdmin,
+
+    #[msg("Market already has bets; only the admin can cancel it")]
+    MarketHasBets,
+
+    #[msg("Market is not cancelled")]
+    MarketNotCancelled,
+
+    #[msg("Invalid tie policy")]
+    InvalidTiePolicy,
+
+    #[msg("Payout schedule must have 1 to 10 non-zero weights summing to 10000 bps")]
+    InvalidPayoutSchedule,
+
+    #[msg("Total cost exceeds max_total_cost")]
+    SlippageExceeded,
+
+    #[msg("Market already holds its maximum number of emojis")]
+    TooManyEmojis,
+
+    #[msg("Emoji cap must be between 1 and 50")]
+    InvalidEmojiCap,
+
+    #[msg("Emoji is not one of the market's choices")]
+    EmojiNotAllowed,
+
+    #[msg("Duplicate emoji id")]
+    DuplicateEmoji,
+
+    #[msg("Emoji sequence must be 1 to 32 bytes")]
+    InvalidEmojiSequence,
+
+    #[msg("Emoji id is not in the registry")]
+    EmojiNotRegistered,
+
+    #[msg("Emoji is disabled in the registry")]
+    EmojiDisabled,
+
+    #[msg("Bet still has winnings or a refund to collect")]
+    UnclaimedFunds,
+
+    #[msg("Claim window must be greater than zero")]
+    InvalidClaimWindow,
+
+    #[msg("Claim window is still open")]
+    ClaimWindowOpen,
+
+    #[msg("Claim window has closed")]
+    ClaimWindowClosed,
+
+    #[msg("Market still has open bet accounts")]
+    OpenBetsRemaining,
+
+    #[msg("Fee recipient does not match the admin or market creator")]
+    InvalidFeeRecipient,
+
+    #[msg("Withdrawal amount must be greater than zero")]
+    InvalidWithdrawAmount,
+
+    #[msg("Treasury balance is too low for this withdrawal")]
+    InsufficientTreasuryFunds,
+
+    #[msg("Crank fee cannot exceed 10000 bps of the platform fee")]
+    InvalidCrankFee,
+
+    #[msg("Only the admin or creator can end the market before the grace period")]
+    GracePeriodActive,
+
+    #[msg("Market creation is paused")]
+    MarketsPaused,
+
+    #[msg("Betting is paused")]
+    BetsPaused,
+
+    #[msg("Withdrawals are paused")]
+    WithdrawalsPaused,
+
+    #[msg("Mint is not accepted for markets")]
+    MintNotAccepted,
+
+    #[msg("Token market requires its mint, vault and token accounts")]
+    MissingTokenAccounts,
+
+    #[msg("Token account does not match the market's mint or owner")]
+    InvalidTokenAccount,
+
+    #[msg("Batch must hold 1 to 50 orders")]
+    InvalidBatch,
+
+    #[msg("Not enough votes on this emoji to sell")]
+    InsufficientVotes,
+
+    #[msg("Position can't be transferred to the same wallet")]
+    SelfTransfer,
+
+    #[msg("Config is already in the current layout")]
+    ConfigAlreadyMigrated,
+
+    #[msg("Config account data is not a legacy Config")]
+    InvalidConfigLayout,
 }
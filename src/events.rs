@@ -0,0 +1,188 @@
+use crate::instructions::quote_bet::BetQuote;
+use crate::state::ConfigParams;
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct ConfigInitialized {
+    pub admin: Pubkey,
+    pub params: ConfigParams,
+}
+
+#[event]
+pub struct ConfigUpdated {
+    pub admin: Pubkey,
+    pub old_params: ConfigParams,
+    pub new_params: ConfigParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ConfigMigrated {
+    pub admin: Pubkey,
+    pub params: ConfigParams,
+}
+
+#[event]
+pub struct AdminTransferProposed {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferCancelled {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub old_admin: Pubkey,
+    pub new_admin: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub admin: Pubkey,
+    pub markets_paused: bool,
+    pub bets_paused: bool,
+    pub withdrawals_paused: bool,
+}
+
+#[event]
+pub struct AcceptedMintSet {
+    pub mint: Pubkey,
+    pub base_price: u64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct EmojiRegistered {
+    pub emoji_id: u32,
+    pub sequence: String,
+}
+
+#[event]
+pub struct EmojiUpdated {
+    pub emoji_id: u32,
+    pub sequence: String,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct MarketCreated {
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub market_id: u64,
+    pub title: String,
+    pub start_ts: i64,
+    pub end_ts: i64,
+    pub tie_policy: u8,
+    pub payout_bps: Vec<u16>,
+    pub max_emojis: u8,
+    pub emoji_ids: Vec<u32>,
+    pub mint: Option<Pubkey>,
+}
+
+#[event]
+pub struct BetPlaced {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub emoji_id: u32,
+    pub vote_qty: u64,
+    pub unit_price: u64,
+    pub total_cost: u64,
+    pub malus_nanos: u64,
+    pub uplift_nanos: u64,
+    pub total_votes: u64,
+    pub total_pot: u64,
+}
+
+#[event]
+pub struct BetsPlaced {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub quotes: Vec<BetQuote>,
+    pub total_cost: u64,
+    pub total_votes: u64,
+    pub total_pot: u64,
+}
+
+#[event]
+pub struct VotesSold {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub emoji_id: u32,
+    pub vote_qty: u64,
+    pub refund: u64,
+    pub spread: u64,
+    pub total_votes: u64,
+    pub total_pot: u64,
+}
+
+#[event]
+pub struct PositionTransferred {
+    pub market: Pubkey,
+    pub from: Pubkey,
+    pub to: Pubkey,
+    pub emoji_id: u32,
+    pub vote_qty: u64,
+    pub spent_moved: u64,
+}
+
+#[event]
+pub struct MarketEnded {
+    pub market: Pubkey,
+    pub ended_by: Pubkey,
+    pub winners: Vec<u32>,
+    pub winner_payouts: Vec<u64>,
+    pub total_pot: u64,
+    pub platform_fee: u64,
+    pub creator_fee: u64,
+    pub crank_fee: u64,
+}
+
+#[event]
+pub struct Claimed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub payout: u64,
+}
+
+#[event]
+pub struct BetClosed {
+    pub market: Pubkey,
+    pub user: Pubkey,
+}
+
+#[event]
+pub struct MarketClosed {
+    pub market: Pubkey,
+    pub closed_by: Pubkey,
+    pub destination: Pubkey,
+    pub swept: u64,
+    pub swept_tokens: u64,
+}
+
+#[event]
+pub struct MarketCancelled {
+    pub market: Pubkey,
+    pub cancelled_by: Pubkey,
+    pub total_pot: u64,
+}
+
+#[event]
+pub struct Refunded {
+    pub market: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct TreasuryWithdrawn {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    // None for SOL
+    pub mint: Option<Pubkey>,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
@@ -0,0 +1,37 @@
+use crate::error::ErrorCode;
+use crate::events::AdminTransferred;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub new_admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    let new_admin = ctx.accounts.new_admin.key();
+
+    // Only the proposed key can complete the transfer, proving it can sign
+    let pending_admin = config.pending_admin.ok_or(ErrorCode::NoPendingAdmin)?;
+    require!(new_admin == pending_admin, ErrorCode::NotPendingAdmin);
+
+    let old_admin = config.admin_address;
+    config.admin_address = new_admin;
+    config.pending_admin = None;
+
+    emit_cpi!(AdminTransferred {
+        old_admin,
+        new_admin,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,23 @@
 use crate::error::ErrorCode;
-use crate::math::{
-    calculate_malus, calculate_quadratic_uplift, calculate_total_cost, calculate_unit_price,
-};
-use crate::state::{BetAccount, Market};
+use crate::events::BetPlaced;
+use crate::instructions::quote_bet::quote;
+use crate::state::{BetAccount, Config, EmojiEntry, Market, MAX_EMOJIS};
+use crate::vault::{require_token_account, require_token_owner, require_vault, transfer_to_vault};
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(market_id: u64)]
+#[instruction(market_id: u64, emoji_id: u32)]
 pub struct PlaceBet<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.bets_paused @ ErrorCode::BetsPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
         seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
@@ -25,70 +34,167 @@ pub struct PlaceBet<'info> {
     )]
     pub bet: Account<'info, BetAccount>,
 
+    #[account(
+        seeds = [b"emoji".as_ref(), &emoji_id.to_le_bytes()],
+        bump = emoji.bump,
+        constraint = emoji.enabled @ ErrorCode::EmojiDisabled
+    )]
+    pub emoji: Account<'info, EmojiEntry>,
+
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Token markets only: the user's account paying for the votes
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the market's mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<PlaceBet>, emoji_id: u32, vote_qty: u64) -> Result<()> {
+pub fn handler(
+    ctx: Context<PlaceBet>,
+    emoji_id: u32,
+    vote_qty: u64,
+    max_total_cost: u64,
+) -> Result<()> {
     let market = &mut ctx.accounts.market;
     let bet = &mut ctx.accounts.bet;
     let user = &ctx.accounts.user;
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
 
-    // Validate vote_qty >= 1
-    require!(vote_qty >= 1, ErrorCode::InvalidVoteQuantity);
+    // Validate the order and price it along the curve
+    let bet_quote = quote(market, now, emoji_id, vote_qty)?;
+    let total_cost = bet_quote.total_cost;
 
-    // Require market Active and now < end_ts
-    require!(market.status == 0, ErrorCode::MarketNotActive);
-    require!(now < market.end_ts, ErrorCode::MarketEnded);
+    // Price moved past what the user accepted (time or other bets)
+    require!(total_cost <= max_total_cost, ErrorCode::SlippageExceeded);
 
-    // Calculate time progress
-    let elapsed = now
-        .checked_sub(market.start_ts)
-        .ok_or(ErrorCode::ArithmeticUnderflow)?;
-    let total_duration = market
-        .end_ts
-        .checked_sub(market.start_ts)
-        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    let received = collect_payment(
+        market,
+        user,
+        &ctx.accounts.system_program,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.token_program,
+        total_cost,
+    )?;
 
-    // Calculate malus
-    let malus_nanos = calculate_malus(elapsed, total_duration, market.malus_k_millis)?;
+    init_bet_if_needed(market, bet, user.key(), ctx.bumps.bet)?;
+    apply_votes(market, bet, emoji_id, vote_qty)?;
+    credit_payment(market, bet, received)?;
 
-    // Calculate quadratic uplift based on current total votes
-    let quad_multiplier_nanos = calculate_quadratic_uplift(
-        market.total_votes,
-        market.quad_a_micros,
-        market.quad_b_micros,
-    )?;
+    emit_cpi!(BetPlaced {
+        market: market.key(),
+        user: user.key(),
+        emoji_id,
+        vote_qty,
+        unit_price: bet_quote.unit_price,
+        total_cost,
+        malus_nanos: bet_quote.malus_nanos,
+        uplift_nanos: bet_quote.uplift_nanos,
+        total_votes: market.total_votes,
+        total_pot: market.total_pot,
+    });
 
-    // Calculate unit price
-    let unit_price = calculate_unit_price(
-        market.base_price_lamports,
-        malus_nanos,
-        quad_multiplier_nanos,
-    )?;
+    Ok(())
+}
 
-    // Calculate total cost
-    let total_cost = calculate_total_cost(unit_price, vote_qty)?;
+/// Take `total_cost` from the user into the market and return the amount
+/// actually received: token mints with a transfer fee deliver less
+#[allow(clippy::too_many_arguments)]
+pub fn collect_payment<'info>(
+    market: &Account<'info, Market>,
+    user: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    user_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    mint: &Option<InterfaceAccount<'info, Mint>>,
+    vault: &mut Option<InterfaceAccount<'info, TokenAccount>>,
+    token_program: &Option<Interface<'info, TokenInterface>>,
+    total_cost: u64,
+) -> Result<u64> {
+    match market.mint {
+        // Transfer tokens from user to the market's vault
+        Some(market_mint) => {
+            let user_token_account = require_token_account(user_token_account)?;
+            let mint = require_token_account(mint)?;
+            let token_program = require_token_account(token_program)?;
+            let vault = vault.as_mut().ok_or(ErrorCode::MissingTokenAccounts)?;
+            require_token_owner(user_token_account, &market_mint, &user.key())?;
+            require_vault(
+                &market.key(),
+                &market_mint,
+                mint,
+                &vault.key(),
+                &token_program.key(),
+            )?;
 
-    // Transfer SOL from user to market account
-    let cpi_context = CpiContext::new(
-        ctx.accounts.system_program.to_account_info(),
-        system_program::Transfer {
-            from: user.to_account_info(),
-            to: market.to_account_info(),
-        },
-    );
-    system_program::transfer(cpi_context, total_cost)?;
+            transfer_to_vault(
+                token_program,
+                mint,
+                user_token_account,
+                vault,
+                &user.to_account_info(),
+                total_cost,
+            )
+        }
+        // Transfer SOL from user to market account
+        None => {
+            let cpi_context = CpiContext::new(
+                system_program.to_account_info(),
+                system_program::Transfer {
+                    from: user.to_account_info(),
+                    to: market.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_context, total_cost)?;
+            Ok(total_cost)
+        }
+    }
+}
 
-    // Update market totals
-    market.total_pot = market
-        .total_pot
-        .checked_add(total_cost)
-        .ok_or(ErrorCode::ArithmeticOverflow)?;
+/// Fill in a BetAccount created by `init_if_needed` and count it as open
+pub fn init_bet_if_needed(
+    market: &mut Account<Market>,
+    bet: &mut BetAccount,
+    user: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    if bet.market == Pubkey::default() {
+        bet.market = market.key();
+        bet.user = user;
+        bet.emoji_ids = Vec::new();
+        bet.emoji_votes = Vec::new();
+        bet.total_spent = 0;
+        bet.claimed = false;
+        bet.bump = bump;
+
+        market.open_bets = market
+            .open_bets
+            .checked_add(1)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    Ok(())
+}
+
+/// Add `vote_qty` votes on `emoji_id` to the market and the user's bet
+pub fn apply_votes(
+    market: &mut Market,
+    bet: &mut BetAccount,
+    emoji_id: u32,
+    vote_qty: u64,
+) -> Result<()> {
     market.total_votes = market
         .total_votes
         .checked_add(vote_qty)
@@ -101,23 +207,16 @@ pub fn handler(ctx: Context<PlaceBet>, emoji_id: u32, vote_qty: u64) -> Result<(
             .checked_add(vote_qty)
             .ok_or(ErrorCode::ArithmeticOverflow)?;
     } else {
-        // New emoji, add to lists
+        // New emoji, `quote` already checked the market's cap
         market.emoji_ids.push(emoji_id);
         market.emoji_votes.push(vote_qty);
     }
 
-    // Initialize bet account if needed
-    if bet.market == Pubkey::default() {
-        bet.market = market.key();
-        bet.user = user.key();
-        bet.emoji_ids = Vec::new();
-        bet.emoji_votes = Vec::new();
-        bet.total_spent = 0;
-        bet.claimed = false;
-        bet.bump = ctx.bumps.bet;
-    }
+    add_bet_votes(bet, emoji_id, vote_qty)
+}
 
-    // Update user's bet
+/// Add `vote_qty` votes on `emoji_id` to the user's bet only
+pub fn add_bet_votes(bet: &mut BetAccount, emoji_id: u32, vote_qty: u64) -> Result<()> {
     if let Some(idx) = bet.emoji_ids.iter().position(|&id| id == emoji_id) {
         // User already voted for this emoji, increment
         bet.emoji_votes[idx] = bet.emoji_votes[idx]
@@ -125,13 +224,57 @@ pub fn handler(ctx: Context<PlaceBet>, emoji_id: u32, vote_qty: u64) -> Result<(
             .ok_or(ErrorCode::ArithmeticOverflow)?;
     } else {
         // First vote for this emoji
+        require!(bet.emoji_ids.len() < MAX_EMOJIS, ErrorCode::TooManyEmojis);
         bet.emoji_ids.push(emoji_id);
         bet.emoji_votes.push(vote_qty);
     }
 
+    Ok(())
+}
+
+/// Take `vote_qty` votes on `emoji_id` out of the user's bet, along with the
+/// matching share of `total_spent`, which is returned
+pub fn remove_bet_votes(bet: &mut BetAccount, emoji_id: u32, vote_qty: u64) -> Result<u64> {
+    let idx = bet
+        .emoji_ids
+        .iter()
+        .position(|&id| id == emoji_id)
+        .ok_or(ErrorCode::InsufficientVotes)?;
+    require!(
+        bet.emoji_votes[idx] >= vote_qty,
+        ErrorCode::InsufficientVotes
+    );
+
+    // The cost basis follows the votes in proportion
+    let bet_votes = bet
+        .emoji_votes
+        .iter()
+        .try_fold(0u64, |acc, &v| acc.checked_add(v))
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let spent = (bet.total_spent as u128)
+        .checked_mul(vote_qty as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(bet_votes as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+
+    bet.emoji_votes[idx] -= vote_qty;
+    bet.total_spent = bet
+        .total_spent
+        .checked_sub(spent)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    Ok(spent)
+}
+
+/// Credit what the market received to its pot and to the user's bet
+pub fn credit_payment(market: &mut Market, bet: &mut BetAccount, received: u64) -> Result<()> {
+    market.total_pot = market
+        .total_pot
+        .checked_add(received)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
     bet.total_spent = bet
         .total_spent
-        .checked_add(total_cost)
+        .checked_add(received)
         .ok_or(ErrorCode::ArithmeticOverflow)?;
 
     Ok(())
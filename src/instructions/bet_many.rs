@@ -0,0 +1,125 @@
+use crate::error::ErrorCode;
+use crate::events::BetsPlaced;
+use crate::instructions::bet::{apply_votes, collect_payment, credit_payment, init_bet_if_needed};
+use crate::instructions::quote_bet::quote;
+use crate::instructions::register_emoji::require_registered;
+use crate::state::{BetAccount, Config, Market, MAX_EMOJIS};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/// One leg of a `bet_many` batch
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BetOrder {
+    pub emoji_id: u32,
+    pub vote_qty: u64,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct BetMany<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.bets_paused @ ErrorCode::BetsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BetAccount::space(),
+        seeds = [b"bet", market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub bet: Account<'info, BetAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token markets only: the user's account paying for the votes
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the market's mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<BetMany>, orders: Vec<BetOrder>, max_total_cost: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let bet = &mut ctx.accounts.bet;
+    let user = &ctx.accounts.user;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !orders.is_empty() && orders.len() <= MAX_EMOJIS,
+        ErrorCode::InvalidBatch
+    );
+
+    // Each emoji must be registered: its EmojiEntry is passed in remaining
+    // accounts, in the same order as the orders
+    require!(
+        ctx.remaining_accounts.len() == orders.len(),
+        ErrorCode::EmojiNotRegistered
+    );
+    for (info, order) in ctx.remaining_accounts.iter().zip(orders.iter()) {
+        require_registered(info, order.emoji_id)?;
+    }
+
+    init_bet_if_needed(market, bet, user.key(), ctx.bumps.bet)?;
+
+    // Orders are priced and applied one after the other, exactly as the same
+    // sequence of `bet` calls would be
+    let mut quotes = Vec::with_capacity(orders.len());
+    let mut total_cost: u64 = 0;
+    for order in orders.iter() {
+        let bet_quote = quote(market, now, order.emoji_id, order.vote_qty)?;
+        apply_votes(market, bet, order.emoji_id, order.vote_qty)?;
+
+        total_cost = total_cost
+            .checked_add(bet_quote.total_cost)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        quotes.push(bet_quote);
+    }
+
+    // Slippage applies to the batch as a whole
+    require!(total_cost <= max_total_cost, ErrorCode::SlippageExceeded);
+
+    let received = collect_payment(
+        market,
+        user,
+        &ctx.accounts.system_program,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.mint,
+        &mut ctx.accounts.vault,
+        &ctx.accounts.token_program,
+        total_cost,
+    )?;
+    credit_payment(market, bet, received)?;
+
+    emit_cpi!(BetsPlaced {
+        market: market.key(),
+        user: user.key(),
+        quotes,
+        total_cost,
+        total_votes: market.total_votes,
+        total_pot: market.total_pot,
+    });
+
+    Ok(())
+}
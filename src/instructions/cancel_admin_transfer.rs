@@ -0,0 +1,32 @@
+use crate::error::ErrorCode;
+use crate::events::AdminTransferCancelled;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct CancelAdminTransfer<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    let pending_admin = config.pending_admin.ok_or(ErrorCode::NoPendingAdmin)?;
+    config.pending_admin = None;
+
+    emit_cpi!(AdminTransferCancelled {
+        admin: config.admin_address,
+        pending_admin,
+    });
+
+    Ok(())
+}
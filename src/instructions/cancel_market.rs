@@ -0,0 +1,57 @@
+use crate::error::ErrorCode;
+use crate::events::MarketCancelled;
+use crate::state::{Config, Market};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct CancelMarket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    pub caller: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelMarket>) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let market = &mut ctx.accounts.market;
+    let caller = &ctx.accounts.caller;
+
+    // Require status = Active
+    require!(market.status == 0, ErrorCode::MarketNotActive);
+
+    // Admin can cancel at any time, creator only before the first bet
+    let is_admin = caller.key() == config.admin_address;
+    let is_creator = caller.key() == market.creator;
+    require!(is_admin || is_creator, ErrorCode::Unauthorized);
+    if !is_admin {
+        require!(market.total_votes == 0, ErrorCode::MarketHasBets);
+    }
+
+    // Mark status = Cancelled; no fees are taken, bettors use `refund`
+    // until the claim window closes
+    market.status = 2;
+    market.claim_deadline_ts = Clock::get()?
+        .unix_timestamp
+        .checked_add(market.claim_window_secs as i64)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit_cpi!(MarketCancelled {
+        market: market.key(),
+        cancelled_by: caller.key(),
+        total_pot: market.total_pot,
+    });
+
+    Ok(())
+}
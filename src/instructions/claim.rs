@@ -1,11 +1,25 @@
 use crate::error::ErrorCode;
+use crate::events::Claimed;
 use crate::math::calculate_user_share;
-use crate::state::{BetAccount, Market};
+use crate::state::{BetAccount, Config, Market};
+use crate::vault::{
+    require_token_account, require_token_owner, require_vault, transfer_from_vault,
+    transfer_lamports,
+};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(market_id: u64)]
 pub struct Claim<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
     #[account(
         mut,
         seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
@@ -23,10 +37,23 @@ pub struct Claim<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
 
+    /// Token markets only: the user's account receiving the payout
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the market's mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<Claim>) -> Result<()> {
+pub fn handler(ctx: Context<Claim>, market_id: u64) -> Result<()> {
     let market = &mut ctx.accounts.market;
     let bet = &mut ctx.accounts.bet;
     let user = &ctx.accounts.user;
@@ -34,61 +61,98 @@ pub fn handler(ctx: Context<Claim>) -> Result<()> {
     // Require market Ended
     require!(market.status == 1, ErrorCode::MarketNotEnded);
 
+    // Require claim window still open
+    require!(
+        Clock::get()?.unix_timestamp < market.claim_deadline_ts,
+        ErrorCode::ClaimWindowClosed
+    );
+
     // Require not yet claimed
     require!(!bet.claimed, ErrorCode::AlreadyClaimed);
 
-    // Get winner emoji
-    let winner_emoji = market.winner.ok_or(ErrorCode::NoWinningVotes)?;
-
-    // Find user's votes on winning emoji
-    let user_winning_votes = bet
-        .emoji_ids
-        .iter()
-        .position(|&id| id == winner_emoji)
-        .and_then(|idx| Some(bet.emoji_votes[idx]))
-        .unwrap_or(0);
-
-    // Require user has votes on winning emoji
-    require!(user_winning_votes > 0, ErrorCode::NoWinningVotes);
-
-    // Find total winning votes
-    let total_winning_votes = market
-        .emoji_ids
-        .iter()
-        .position(|&id| id == winner_emoji)
-        .and_then(|idx| Some(market.emoji_votes[idx]))
-        .unwrap_or(0);
-
-    require!(total_winning_votes > 0, ErrorCode::InvalidCalculation);
-
-    // Calculate payout pool = pot - fees
-    let payout_pool = market
-        .total_pot
-        .checked_sub(market.platform_fee_taken)
-        .ok_or(ErrorCode::ArithmeticUnderflow)?
-        .checked_sub(market.creator_fee_taken)
-        .ok_or(ErrorCode::ArithmeticUnderflow)?;
-
-    // Calculate user share
-    let user_share = calculate_user_share(payout_pool, user_winning_votes, total_winning_votes)?;
+    // Sum the user's share of each winning emoji's payout pool
+    let user_share = calculate_payout(market, bet)?;
+
+    // Require user has votes on a winning emoji
+    require!(user_share > 0, ErrorCode::NoWinningVotes);
 
     // Transfer from market to user
-    if user_share > 0 {
-        **market.to_account_info().try_borrow_mut_lamports()? = market
-            .to_account_info()
-            .lamports()
-            .checked_sub(user_share)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
-
-        **user.to_account_info().try_borrow_mut_lamports()? = user
-            .to_account_info()
-            .lamports()
-            .checked_add(user_share)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    match market.mint {
+        Some(mint) => {
+            let user_token_account = require_token_account(&ctx.accounts.user_token_account)?;
+            let mint_account = require_token_account(&ctx.accounts.mint)?;
+            let vault = require_token_account(&ctx.accounts.vault)?;
+            let token_program = require_token_account(&ctx.accounts.token_program)?;
+            require_token_owner(user_token_account, &mint, &user.key())?;
+            require_vault(
+                &market.key(),
+                &mint,
+                mint_account,
+                &vault.key(),
+                &token_program.key(),
+            )?;
+            transfer_from_vault(
+                token_program,
+                mint_account,
+                vault,
+                user_token_account,
+                market,
+                market_id,
+                user_share,
+            )?;
+        }
+        None => transfer_lamports(
+            &market.to_account_info(),
+            &user.to_account_info(),
+            user_share,
+        )?,
     }
 
     // Mark claimed
     bet.claimed = true;
 
+    emit_cpi!(Claimed {
+        market: market.key(),
+        user: user.key(),
+        payout: user_share,
+    });
+
     Ok(())
 }
+
+/// Compute what a bet is owed on an ended market: for every winning emoji,
+/// payout_pool * user_votes / total_votes on that emoji (rounded down)
+pub fn calculate_payout(market: &Market, bet: &BetAccount) -> Result<u64> {
+    let mut payout: u64 = 0;
+
+    for (&winner_emoji, &payout_pool) in market.winners.iter().zip(market.winner_payouts.iter()) {
+        // Find user's votes on winning emoji
+        let user_winning_votes = bet
+            .emoji_ids
+            .iter()
+            .position(|&id| id == winner_emoji)
+            .map(|idx| bet.emoji_votes[idx])
+            .unwrap_or(0);
+
+        if user_winning_votes == 0 {
+            continue;
+        }
+
+        // Find total winning votes
+        let total_winning_votes = market
+            .emoji_ids
+            .iter()
+            .position(|&id| id == winner_emoji)
+            .map(|idx| market.emoji_votes[idx])
+            .unwrap_or(0);
+
+        require!(total_winning_votes > 0, ErrorCode::InvalidCalculation);
+
+        let share = calculate_user_share(payout_pool, user_winning_votes, total_winning_votes)?;
+        payout = payout
+            .checked_add(share)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    Ok(payout)
+}
@@ -0,0 +1,79 @@
+use crate::error::ErrorCode;
+use crate::events::BetClosed;
+use crate::instructions::claim::calculate_payout;
+use crate::state::{BetAccount, Config, Market};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct CloseBet<'info> {
+    // Closing after the deadline forfeits unclaimed funds, so it waits out a pause
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        close = user,
+        seeds = [b"bet", market.key().as_ref(), user.key().as_ref()],
+        bump = bet.bump
+    )]
+    pub bet: Account<'info, BetAccount>,
+
+    /// CHECK: Bet owner, receives the rent; bound to the bet by its seeds
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+
+    pub closer: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CloseBet>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let bet = &ctx.accounts.bet;
+    let closer = &ctx.accounts.closer;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Require market Ended or Cancelled
+    require!(
+        market.status == 1 || market.status == 2,
+        ErrorCode::MarketNotEnded
+    );
+
+    if now < market.claim_deadline_ts {
+        // During the claim window only the owner can close, and only once
+        // nothing is left to collect: the bet was claimed/refunded or never
+        // had anything to claim
+        require!(closer.key() == bet.user, ErrorCode::Unauthorized);
+        let settled = match market.status {
+            1 => bet.claimed || calculate_payout(market, bet)? == 0, // Ended
+            _ => bet.claimed || bet.total_spent == 0,                // Cancelled
+        };
+        require!(settled, ErrorCode::UnclaimedFunds);
+    }
+    // After the window anyone can close it so the market can be closed too;
+    // the rent still goes back to the owner. The bet can't be re-created
+    // since `bet` only runs on active markets.
+
+    market.open_bets = market
+        .open_bets
+        .checked_sub(1)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    emit_cpi!(BetClosed {
+        market: market.key(),
+        user: bet.user,
+    });
+
+    Ok(())
+}
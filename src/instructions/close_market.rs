@@ -0,0 +1,151 @@
+use crate::error::ErrorCode;
+use crate::events::MarketClosed;
+use crate::state::{Config, Market, Treasury};
+use crate::vault::{
+    close_vault, harvest_vault_fees, require_token_account, require_token_owner, require_vault,
+    transfer_from_vault, transfer_lamports,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct CloseMarket<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        close = market_creator,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    pub caller: Signer<'info>,
+
+    /// CHECK: Market creator, gets back the market's rent
+    #[account(mut, address = market.creator)]
+    pub market_creator: AccountInfo<'info>,
+
+    /// Receives unclaimed funds and rounding dust
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Token markets only: the market's mint, receiving the vault's withheld
+    /// transfer fees for Token-2022 mints
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault, closed along with the market
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the treasury's account receiving unclaimed tokens
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handler(ctx: Context<CloseMarket>, market_id: u64) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let market = &mut ctx.accounts.market;
+    let caller = &ctx.accounts.caller;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Validate caller is admin or creator
+    let is_admin = caller.key() == config.admin_address;
+    let is_creator = caller.key() == market.creator;
+    require!(is_admin || is_creator, ErrorCode::Unauthorized);
+
+    // Require market Ended or Cancelled
+    require!(
+        market.status == 1 || market.status == 2,
+        ErrorCode::MarketNotEnded
+    );
+
+    // Wait for the claim window unless there was never anything to claim
+    require!(
+        now >= market.claim_deadline_ts || market.total_pot == 0,
+        ErrorCode::ClaimWindowOpen
+    );
+
+    // Every BetAccount must be closed first, otherwise a market re-created
+    // under the same id would pick up stale bets at the same address
+    require!(market.open_bets == 0, ErrorCode::OpenBetsRemaining);
+
+    // Sweep everything above rent: unclaimed winnings, refunds and dust.
+    // The rent itself goes back to the creator when the account is closed.
+    let market_info = market.to_account_info();
+    let rent_exempt = Rent::get()?.minimum_balance(market_info.data_len());
+    let swept = market_info.lamports().saturating_sub(rent_exempt);
+
+    if swept > 0 {
+        let treasury = &mut ctx.accounts.treasury;
+        transfer_lamports(&market_info, &treasury.to_account_info(), swept)?;
+        treasury.total_collected = treasury
+            .total_collected
+            .checked_add(swept)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    }
+
+    // Token markets: the vault is emptied the same way, then closed with its
+    // rent going back to the creator who paid for it
+    let mut swept_tokens = 0;
+    if let Some(mint) = market.mint {
+        let mint_account = require_token_account(&ctx.accounts.mint)?;
+        let vault = require_token_account(&ctx.accounts.vault)?;
+        let treasury_token_account = require_token_account(&ctx.accounts.treasury_token_account)?;
+        let token_program = require_token_account(&ctx.accounts.token_program)?;
+        require_vault(
+            &market.key(),
+            &mint,
+            mint_account,
+            &vault.key(),
+            &token_program.key(),
+        )?;
+        require_token_owner(treasury_token_account, &mint, &ctx.accounts.treasury.key())?;
+
+        swept_tokens = vault.amount;
+        if swept_tokens > 0 {
+            transfer_from_vault(
+                token_program,
+                mint_account,
+                vault,
+                treasury_token_account,
+                market,
+                market_id,
+                swept_tokens,
+            )?;
+        }
+
+        harvest_vault_fees(token_program, mint_account, vault)?;
+        close_vault(
+            token_program,
+            vault,
+            &ctx.accounts.market_creator,
+            market,
+            market_id,
+        )?;
+    }
+
+    emit_cpi!(MarketClosed {
+        market: market.key(),
+        closed_by: caller.key(),
+        destination: ctx.accounts.treasury.key(),
+        swept,
+        swept_tokens,
+    });
+
+    Ok(())
+}
@@ -1,13 +1,19 @@
 use crate::error::ErrorCode;
-use crate::state::{Config, Market};
+use crate::events::MarketCreated;
+use crate::instructions::register_emoji::require_registered;
+use crate::state::{AcceptedMint, Config, Market, TiePolicy, MAX_EMOJIS, MAX_PAYOUT_TIERS};
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(market_id: u64)]
 pub struct CreateMarket<'info> {
     #[account(
         seeds = [b"config"],
-        bump = config.bump
+        bump = config.bump,
+        constraint = !config.markets_paused @ ErrorCode::MarketsPaused
     )]
     pub config: Account<'info, Config>,
 
@@ -23,15 +29,44 @@ pub struct CreateMarket<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
 
+    /// Token markets only: the mint the market is denominated in
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"accepted_mint", mint.as_ref().map(|m| m.key()).unwrap_or_default().as_ref()],
+        bump = accepted_mint.bump,
+        constraint = accepted_mint.enabled @ ErrorCode::MintNotAccepted
+    )]
+    pub accepted_mint: Option<Account<'info, AcceptedMint>>,
+
+    /// Token markets only: the market's associated token account holding the pot
+    #[account(
+        init,
+        payer = creator,
+        associated_token::mint = mint,
+        associated_token::authority = market,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateMarket>,
-    _market_id: u64,
+    market_id: u64,
     title: String,
     image_url: Option<String>,
     end_ts: i64,
+    tie_policy: u8,
+    payout_bps: Vec<u16>,
+    max_emojis: Option<u8>,
+    allowed_emoji_ids: Vec<u32>,
 ) -> Result<()> {
     let config = &ctx.accounts.config;
     let market = &mut ctx.accounts.market;
@@ -46,6 +81,61 @@ pub fn handler(
         require!(url.len() <= 200, ErrorCode::ImageUrlTooLong);
     }
 
+    // Validate emoji cap, defaults to MAX_EMOJIS
+    let max_emojis = max_emojis.unwrap_or(MAX_EMOJIS as u8);
+    require!(
+        max_emojis >= 1 && max_emojis as usize <= MAX_EMOJIS,
+        ErrorCode::InvalidEmojiCap
+    );
+
+    // Validate creator-defined choices: unique ids within the emoji cap
+    require!(
+        allowed_emoji_ids.len() <= max_emojis as usize,
+        ErrorCode::TooManyEmojis
+    );
+    for (idx, id) in allowed_emoji_ids.iter().enumerate() {
+        require!(
+            !allowed_emoji_ids[..idx].contains(id),
+            ErrorCode::DuplicateEmoji
+        );
+    }
+
+    // Each choice must be registered: its EmojiEntry is passed in remaining
+    // accounts, in the same order as allowed_emoji_ids
+    require!(
+        ctx.remaining_accounts.len() == allowed_emoji_ids.len(),
+        ErrorCode::EmojiNotRegistered
+    );
+    for (info, &id) in ctx.remaining_accounts.iter().zip(allowed_emoji_ids.iter()) {
+        require_registered(info, id)?;
+    }
+
+    // Validate tie policy
+    require!(
+        tie_policy <= TiePolicy::Split as u8,
+        ErrorCode::InvalidTiePolicy
+    );
+
+    // Validate payout schedule: winner-takes-all when empty, otherwise
+    // at most MAX_PAYOUT_TIERS non-zero weights summing to 10000
+    let payout_bps = if payout_bps.is_empty() {
+        vec![10000]
+    } else {
+        payout_bps
+    };
+    require!(
+        payout_bps.len() <= MAX_PAYOUT_TIERS,
+        ErrorCode::InvalidPayoutSchedule
+    );
+    require!(
+        payout_bps.iter().all(|&bps| bps > 0),
+        ErrorCode::InvalidPayoutSchedule
+    );
+    require!(
+        payout_bps.iter().map(|&bps| bps as u32).sum::<u32>() == 10000,
+        ErrorCode::InvalidPayoutSchedule
+    );
+
     // Set start_ts = now
     let start_ts = now;
 
@@ -75,14 +165,36 @@ pub fn handler(
     market.status = 0; // Active
     market.total_pot = 0;
     market.total_votes = 0;
-    market.emoji_ids = Vec::new();
-    market.emoji_votes = Vec::new();
-    market.winner = None;
+    // Choices start with zero votes; bets are then limited to them
+    market.emoji_votes = vec![0; allowed_emoji_ids.len()];
+    market.restricted_emojis = !allowed_emoji_ids.is_empty();
+    market.emoji_ids = allowed_emoji_ids;
+    market.max_emojis = max_emojis;
+    market.tie_policy = tie_policy;
+    market.payout_bps = payout_bps;
+    market.winners = Vec::new();
+    market.winner_payouts = Vec::new();
     market.platform_fee_taken = 0;
     market.creator_fee_taken = 0;
 
+    // Token markets price votes in the mint's units, set by the admin per mint
+    market.mint = match (&ctx.accounts.mint, &ctx.accounts.accepted_mint) {
+        (Some(mint), Some(_)) => {
+            require!(
+                ctx.accounts.vault.is_some(),
+                ErrorCode::MissingTokenAccounts
+            );
+            Some(mint.key())
+        }
+        (None, None) => None,
+        _ => return err!(ErrorCode::MissingTokenAccounts),
+    };
+
     // Snapshot pricing/fee params from Config
-    market.base_price_lamports = config.base_price_lamports;
+    market.base_price_lamports = match &ctx.accounts.accepted_mint {
+        Some(accepted_mint) => accepted_mint.base_price,
+        None => config.base_price_lamports,
+    };
     market.malus_k_millis = config.malus_k_millis;
     market.quad_a_micros = config.quad_a_micros;
     market.quad_b_micros = config.quad_b_micros;
@@ -90,7 +202,26 @@ pub fn handler(
     market.creator_fee_bps = config.creator_fee_bps;
     market.min_duration_secs = config.min_duration_secs;
     market.max_duration_secs = config.max_duration_secs;
+    market.claim_window_secs = config.claim_window_secs;
+    market.end_grace_secs = config.end_grace_secs;
+    market.crank_fee_bps = config.crank_fee_bps;
+    market.claim_deadline_ts = 0;
+    market.open_bets = 0;
     market.bump = ctx.bumps.market;
 
+    emit_cpi!(MarketCreated {
+        market: market.key(),
+        creator: market.creator,
+        market_id,
+        title: market.title.clone(),
+        start_ts,
+        end_ts,
+        tie_policy,
+        payout_bps: market.payout_bps.clone(),
+        max_emojis,
+        emoji_ids: market.emoji_ids.clone(),
+        mint: market.mint,
+    });
+
     Ok(())
 }
@@ -1,14 +1,22 @@
 use crate::error::ErrorCode;
-use crate::math::calculate_fee;
-use crate::state::{Config, Market};
+use crate::events::{MarketCancelled, MarketEnded};
+use crate::math::{calculate_fee, calculate_tier_payouts};
+use crate::state::{Config, Market, TiePolicy, Treasury};
+use crate::vault::{
+    require_token_account, require_token_owner, require_vault, transfer_from_vault,
+    transfer_lamports,
+};
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(market_id: u64)]
 pub struct EndMarket<'info> {
     #[account(
         seeds = [b"config"],
-        bump = config.bump
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
     )]
     pub config: Account<'info, Config>,
 
@@ -22,92 +30,202 @@ pub struct EndMarket<'info> {
     #[account(mut)]
     pub caller: Signer<'info>,
 
-    /// CHECK: Platform admin to receive fees
-    #[account(mut)]
-    pub platform_admin: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
 
     /// CHECK: Market creator to receive fees
-    #[account(mut)]
+    #[account(mut, address = market.creator @ ErrorCode::InvalidFeeRecipient)]
     pub market_creator: AccountInfo<'info>,
 
+    /// Token markets only: the market's mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the treasury's account for the market's mint
+    #[account(mut)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the creator's account for the market's mint
+    #[account(mut)]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the caller's account, needed when a crank fee is paid
+    #[account(mut)]
+    pub caller_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<EndMarket>) -> Result<()> {
+pub fn handler(ctx: Context<EndMarket>, market_id: u64) -> Result<()> {
     let config = &ctx.accounts.config;
     let market = &mut ctx.accounts.market;
     let caller = &ctx.accounts.caller;
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
 
-    // Validate caller is admin or creator
-    let is_admin = caller.key() == config.admin_address;
-    let is_creator = caller.key() == market.creator;
-    require!(is_admin || is_creator, ErrorCode::Unauthorized);
-
     // Require now >= end_ts
     require!(now >= market.end_ts, ErrorCode::MarketNotEnded);
 
+    // Admin and creator can end right away; anyone else once the grace
+    // period has passed, earning the crank fee for resolving the market
+    let is_admin = caller.key() == config.admin_address;
+    let is_creator = caller.key() == market.creator;
+    let is_crank = !is_admin && !is_creator;
+    if is_crank {
+        let grace_end = market
+            .end_ts
+            .checked_add(market.end_grace_secs as i64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        require!(now >= grace_end, ErrorCode::GracePeriodActive);
+    }
+
     // Require status = Active
     require!(market.status == 0, ErrorCode::MarketNotActive);
 
-    // Find winner: emoji with highest votes (tie-break: lowest emoji_id)
-    let mut winner_emoji: Option<u32> = None;
-    let mut max_votes: u64 = 0;
-
-    for (idx, &votes) in market.emoji_votes.iter().enumerate() {
-        let emoji_id = market.emoji_ids[idx];
-        if votes > max_votes
-            || (votes == max_votes && (winner_emoji.is_none() || emoji_id < winner_emoji.unwrap()))
-        {
-            max_votes = votes;
-            winner_emoji = Some(emoji_id);
-        }
-    }
+    // No votes means no winner: void the market instead of leaving the pot
+    // unclaimable. It is marked Cancelled without fees so any bettor can `refund`.
+    if market.total_votes == 0 {
+        market.status = 2;
+        market.claim_deadline_ts = now
+            .checked_add(market.claim_window_secs as i64)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-    market.winner = winner_emoji;
+        emit_cpi!(MarketCancelled {
+            market: market.key(),
+            cancelled_by: caller.key(),
+            total_pot: market.total_pot,
+        });
+
+        return Ok(());
+    }
 
     // Compute fees
     let platform_fee = calculate_fee(market.total_pot, market.platform_fee_bps)?;
     let creator_fee = calculate_fee(market.total_pot, market.creator_fee_bps)?;
 
+    // Crank reward comes out of the platform's share only
+    let crank_fee = if is_crank {
+        calculate_fee(platform_fee, market.crank_fee_bps)?
+    } else {
+        0
+    };
+    let treasury_fee = platform_fee
+        .checked_sub(crank_fee)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
     market.platform_fee_taken = platform_fee;
     market.creator_fee_taken = creator_fee;
 
-    // Transfer platform fee
-    if platform_fee > 0 {
-        **market.to_account_info().try_borrow_mut_lamports()? = market
-            .to_account_info()
-            .lamports()
-            .checked_sub(platform_fee)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
-
-        **ctx.accounts.platform_admin.try_borrow_mut_lamports()? = ctx
-            .accounts
-            .platform_admin
-            .lamports()
-            .checked_add(platform_fee)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
-    }
-
-    // Transfer creator fee
-    if creator_fee > 0 {
-        **market.to_account_info().try_borrow_mut_lamports()? = market
-            .to_account_info()
-            .lamports()
-            .checked_sub(creator_fee)
-            .ok_or(ErrorCode::ArithmeticUnderflow)?;
-
-        **ctx.accounts.market_creator.try_borrow_mut_lamports()? = ctx
-            .accounts
-            .market_creator
-            .lamports()
-            .checked_add(creator_fee)
-            .ok_or(ErrorCode::ArithmeticOverflow)?;
+    // Payout pool = pot - fees, split across the top places by the market's
+    // payout schedule, ties resolved by its tie policy
+    let payout_pool = market
+        .total_pot
+        .checked_sub(platform_fee)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?
+        .checked_sub(creator_fee)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    let split_ties = market.tie_policy == TiePolicy::Split as u8;
+    let (winners, winner_payouts) = calculate_tier_payouts(
+        &market.emoji_ids,
+        &market.emoji_votes,
+        &market.payout_bps,
+        split_ties,
+        payout_pool,
+    )?;
+
+    market.winners = winners;
+    market.winner_payouts = winner_payouts;
+
+    // Pay fees: the platform's share net of the crank reward into the
+    // treasury, the crank reward to the caller and the creator's share
+    match market.mint {
+        Some(mint) => {
+            let mint_account = require_token_account(&ctx.accounts.mint)?;
+            let vault = require_token_account(&ctx.accounts.vault)?;
+            let token_program = require_token_account(&ctx.accounts.token_program)?;
+            require_vault(
+                &market.key(),
+                &mint,
+                mint_account,
+                &vault.key(),
+                &token_program.key(),
+            )?;
+
+            for (amount, recipient, owner) in [
+                (
+                    treasury_fee,
+                    &ctx.accounts.treasury_token_account,
+                    ctx.accounts.treasury.key(),
+                ),
+                (crank_fee, &ctx.accounts.caller_token_account, caller.key()),
+                (
+                    creator_fee,
+                    &ctx.accounts.creator_token_account,
+                    market.creator,
+                ),
+            ] {
+                if amount > 0 {
+                    let recipient = require_token_account(recipient)?;
+                    require_token_owner(recipient, &mint, &owner)?;
+                    transfer_from_vault(
+                        token_program,
+                        mint_account,
+                        vault,
+                        recipient,
+                        market,
+                        market_id,
+                        amount,
+                    )?;
+                }
+            }
+        }
+        None => {
+            let market_info = market.to_account_info();
+            let treasury = &mut ctx.accounts.treasury;
+            if treasury_fee > 0 {
+                transfer_lamports(&market_info, &treasury.to_account_info(), treasury_fee)?;
+
+                // Lifetime totals are kept in lamports only
+                treasury.total_collected = treasury
+                    .total_collected
+                    .checked_add(treasury_fee)
+                    .ok_or(ErrorCode::ArithmeticOverflow)?;
+            }
+            if crank_fee > 0 {
+                transfer_lamports(&market_info, &caller.to_account_info(), crank_fee)?;
+            }
+            if creator_fee > 0 {
+                transfer_lamports(&market_info, &ctx.accounts.market_creator, creator_fee)?;
+            }
+        }
     }
 
-    // Mark status = Ended
+    // Mark status = Ended, winners can claim until the claim window closes
     market.status = 1;
+    market.claim_deadline_ts = now
+        .checked_add(market.claim_window_secs as i64)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit_cpi!(MarketEnded {
+        market: market.key(),
+        ended_by: caller.key(),
+        winners: market.winners.clone(),
+        winner_payouts: market.winner_payouts.clone(),
+        total_pot: market.total_pot,
+        platform_fee,
+        creator_fee,
+        crank_fee,
+    });
 
     Ok(())
 }
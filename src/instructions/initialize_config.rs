@@ -1,7 +1,9 @@
 use crate::error::ErrorCode;
-use crate::state::Config;
+use crate::events::ConfigInitialized;
+use crate::state::{Config, Treasury};
 use anchor_lang::prelude::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
     #[account(
@@ -13,12 +15,22 @@ pub struct InitializeConfig<'info> {
     )]
     pub config: Account<'info, Config>,
 
+    #[account(
+        init,
+        payer = payer,
+        space = Treasury::LEN,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<InitializeConfig>,
     admin_address: Pubkey,
@@ -30,6 +42,61 @@ pub fn handler(
     quad_b_micros: u64,
     min_duration_secs: u32,
     max_duration_secs: u32,
+    claim_window_secs: u32,
+    end_grace_secs: u32,
+    crank_fee_bps: u16,
+) -> Result<()> {
+    validate_params(
+        platform_fee_bps,
+        creator_fee_bps,
+        base_price_lamports,
+        min_duration_secs,
+        max_duration_secs,
+        claim_window_secs,
+        crank_fee_bps,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    config.admin_address = admin_address;
+    config.platform_fee_bps = platform_fee_bps;
+    config.creator_fee_bps = creator_fee_bps;
+    config.base_price_lamports = base_price_lamports;
+    config.malus_k_millis = malus_k_millis;
+    config.quad_a_micros = quad_a_micros;
+    config.quad_b_micros = quad_b_micros;
+    config.min_duration_secs = min_duration_secs;
+    config.max_duration_secs = max_duration_secs;
+    config.claim_window_secs = claim_window_secs;
+    config.end_grace_secs = end_grace_secs;
+    config.crank_fee_bps = crank_fee_bps;
+    config.markets_paused = false;
+    config.bets_paused = false;
+    config.withdrawals_paused = false;
+    config.pending_admin = None;
+    config.bump = ctx.bumps.config;
+
+    let treasury = &mut ctx.accounts.treasury;
+    treasury.total_collected = 0;
+    treasury.total_withdrawn = 0;
+    treasury.bump = ctx.bumps.treasury;
+
+    emit_cpi!(ConfigInitialized {
+        admin: admin_address,
+        params: config.params(),
+    });
+
+    Ok(())
+}
+
+/// Checks shared by `initialize_config` and `update_config`
+pub fn validate_params(
+    platform_fee_bps: u16,
+    creator_fee_bps: u16,
+    base_price_lamports: u64,
+    min_duration_secs: u32,
+    max_duration_secs: u32,
+    claim_window_secs: u32,
+    crank_fee_bps: u16,
 ) -> Result<()> {
     // Validate: platform_fee_bps + creator_fee_bps <= 10000
     require!(
@@ -46,17 +113,11 @@ pub fn handler(
     // Validate base_price_lamports > 0
     require!(base_price_lamports > 0, ErrorCode::InvalidBasePrice);
 
-    let config = &mut ctx.accounts.config;
-    config.admin_address = admin_address;
-    config.platform_fee_bps = platform_fee_bps;
-    config.creator_fee_bps = creator_fee_bps;
-    config.base_price_lamports = base_price_lamports;
-    config.malus_k_millis = malus_k_millis;
-    config.quad_a_micros = quad_a_micros;
-    config.quad_b_micros = quad_b_micros;
-    config.min_duration_secs = min_duration_secs;
-    config.max_duration_secs = max_duration_secs;
-    config.bump = ctx.bumps.config;
+    // Validate claim_window_secs > 0 so winners always get time to claim
+    require!(claim_window_secs > 0, ErrorCode::InvalidClaimWindow);
+
+    // Validate crank_fee_bps <= 10000, it is a share of the platform fee
+    require!(crank_fee_bps <= 10000, ErrorCode::InvalidCrankFee);
 
     Ok(())
 }
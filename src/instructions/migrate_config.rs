@@ -0,0 +1,136 @@
+use crate::error::ErrorCode;
+use crate::events::ConfigMigrated;
+use crate::instructions::initialize_config::validate_params;
+use crate::state::{Config, Treasury};
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+
+/// Config as first deployed, before the claim window, crank, pause and
+/// admin transfer fields were added
+#[derive(AnchorDeserialize)]
+struct LegacyConfig {
+    admin_address: Pubkey,
+    platform_fee_bps: u16,
+    creator_fee_bps: u16,
+    base_price_lamports: u64,
+    malus_k_millis: u32,
+    quad_a_micros: u64,
+    quad_b_micros: u64,
+    min_duration_secs: u32,
+    max_duration_secs: u32,
+    bump: u8,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateConfig<'info> {
+    /// CHECK: Still in the legacy layout, so it is decoded by hand in the handler
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump,
+        owner = crate::ID,
+        constraint = config.data_len() < Config::LEN @ ErrorCode::ConfigAlreadyMigrated
+    )]
+    pub config: UncheckedAccount<'info>,
+
+    // init_if_needed so a repeated call fails on the config check above
+    // rather than on the treasury already existing
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = Treasury::LEN,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<MigrateConfig>,
+    claim_window_secs: u32,
+    end_grace_secs: u32,
+    crank_fee_bps: u16,
+) -> Result<()> {
+    let config_info = ctx.accounts.config.to_account_info();
+
+    let legacy = {
+        let data = config_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Config::DISCRIMINATOR,
+            ErrorCode::InvalidConfigLayout
+        );
+        LegacyConfig::deserialize(&mut &data[8..])
+            .map_err(|_| error!(ErrorCode::InvalidConfigLayout))?
+    };
+    require!(
+        legacy.admin_address == ctx.accounts.admin.key(),
+        ErrorCode::Unauthorized
+    );
+
+    validate_params(
+        legacy.platform_fee_bps,
+        legacy.creator_fee_bps,
+        legacy.base_price_lamports,
+        legacy.min_duration_secs,
+        legacy.max_duration_secs,
+        claim_window_secs,
+        crank_fee_bps,
+    )?;
+
+    // The admin tops the account up to rent exemption at its new size
+    let rent_due = Rent::get()?
+        .minimum_balance(Config::LEN)
+        .saturating_sub(config_info.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: config_info.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+    config_info.realloc(Config::LEN, true)?;
+
+    // New fields start from the same defaults initialize_config uses
+    let config = Config {
+        admin_address: legacy.admin_address,
+        platform_fee_bps: legacy.platform_fee_bps,
+        creator_fee_bps: legacy.creator_fee_bps,
+        base_price_lamports: legacy.base_price_lamports,
+        malus_k_millis: legacy.malus_k_millis,
+        quad_a_micros: legacy.quad_a_micros,
+        quad_b_micros: legacy.quad_b_micros,
+        min_duration_secs: legacy.min_duration_secs,
+        max_duration_secs: legacy.max_duration_secs,
+        claim_window_secs,
+        end_grace_secs,
+        crank_fee_bps,
+        markets_paused: false,
+        bets_paused: false,
+        withdrawals_paused: false,
+        pending_admin: None,
+        bump: legacy.bump,
+    };
+    config.try_serialize(&mut &mut config_info.try_borrow_mut_data()?[..])?;
+
+    // A fresh treasury is zeroed, only its bump needs setting
+    ctx.accounts.treasury.bump = ctx.bumps.treasury;
+
+    emit_cpi!(ConfigMigrated {
+        admin: config.admin_address,
+        params: config.params(),
+    });
+
+    Ok(())
+}
@@ -1,11 +1,47 @@
+pub mod accept_admin;
 pub mod bet;
+pub mod bet_many;
+pub mod cancel_admin_transfer;
+pub mod cancel_market;
 pub mod claim;
+pub mod close_bet;
+pub mod close_market;
 pub mod create_market;
 pub mod end_market;
 pub mod initialize_config;
+pub mod migrate_config;
+pub mod propose_admin;
+pub mod quote_bet;
+pub mod refund;
+pub mod register_emoji;
+pub mod sell_votes;
+pub mod set_accepted_mint;
+pub mod set_pause;
+pub mod transfer_position;
+pub mod update_config;
+pub mod update_emoji;
+pub mod withdraw_treasury;
 
+pub use accept_admin::*;
 pub use bet::*;
+pub use bet_many::*;
+pub use cancel_admin_transfer::*;
+pub use cancel_market::*;
 pub use claim::*;
+pub use close_bet::*;
+pub use close_market::*;
 pub use create_market::*;
 pub use end_market::*;
 pub use initialize_config::*;
+pub use migrate_config::*;
+pub use propose_admin::*;
+pub use quote_bet::*;
+pub use refund::*;
+pub use register_emoji::*;
+pub use sell_votes::*;
+pub use set_accepted_mint::*;
+pub use set_pause::*;
+pub use transfer_position::*;
+pub use update_config::*;
+pub use update_emoji::*;
+pub use withdraw_treasury::*;
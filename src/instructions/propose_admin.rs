@@ -0,0 +1,33 @@
+use crate::error::ErrorCode;
+use crate::events::AdminTransferProposed;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    require!(new_admin != Pubkey::default(), ErrorCode::InvalidAdmin);
+
+    // The current admin stays in charge until new_admin accepts
+    let config = &mut ctx.accounts.config;
+    config.pending_admin = Some(new_admin);
+
+    emit_cpi!(AdminTransferProposed {
+        admin: config.admin_address,
+        pending_admin: new_admin,
+    });
+
+    Ok(())
+}
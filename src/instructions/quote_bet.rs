@@ -0,0 +1,114 @@
+use crate::error::ErrorCode;
+use crate::math::{
+    calculate_malus, calculate_quadratic_uplift, calculate_quadratic_uplift_sum,
+    calculate_total_cost, calculate_unit_price,
+};
+use crate::state::{EmojiEntry, Market};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+#[instruction(market_id: u64, emoji_id: u32)]
+pub struct QuoteBet<'info> {
+    #[account(
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"emoji".as_ref(), &emoji_id.to_le_bytes()],
+        bump = emoji.bump,
+        constraint = emoji.enabled @ ErrorCode::EmojiDisabled
+    )]
+    pub emoji: Account<'info, EmojiEntry>,
+}
+
+/// Price of a bet, returned by `quote_bet` and charged by `bet`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BetQuote {
+    pub emoji_id: u32,
+    pub vote_qty: u64,
+    /// Time malus * 1e9
+    pub malus_nanos: u64,
+    /// Quadratic uplift f(n) on the current total votes * 1e9
+    pub uplift_nanos: u64,
+    /// Spot price of the next vote
+    pub unit_price: u64,
+    /// Cost of the whole order along the curve
+    pub total_cost: u64,
+}
+
+/// Read-only: publishes the quote as return data so clients can get the exact
+/// on-chain price through `simulateTransaction`
+pub fn handler(ctx: Context<QuoteBet>, emoji_id: u32, vote_qty: u64) -> Result<BetQuote> {
+    let now = Clock::get()?.unix_timestamp;
+    quote(&ctx.accounts.market, now, emoji_id, vote_qty)
+}
+
+/// Validate an order against the market and price it at `now`
+pub fn quote(market: &Market, now: i64, emoji_id: u32, vote_qty: u64) -> Result<BetQuote> {
+    // Validate vote_qty >= 1
+    require!(vote_qty >= 1, ErrorCode::InvalidVoteQuantity);
+
+    // Require market Active and now < end_ts
+    require!(market.status == 0, ErrorCode::MarketNotActive);
+    require!(now < market.end_ts, ErrorCode::MarketEnded);
+
+    // Markets created with a list of choices only accept those
+    if market.restricted_emojis {
+        require!(
+            market.emoji_ids.contains(&emoji_id),
+            ErrorCode::EmojiNotAllowed
+        );
+    }
+
+    // A new emoji needs room under the market's cap
+    if !market.emoji_ids.contains(&emoji_id) {
+        require!(
+            market.emoji_ids.len() < market.max_emojis as usize,
+            ErrorCode::TooManyEmojis
+        );
+    }
+
+    // Calculate time progress
+    let elapsed = now
+        .checked_sub(market.start_ts)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    let total_duration = market
+        .end_ts
+        .checked_sub(market.start_ts)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    // Calculate malus
+    let malus_nanos = calculate_malus(elapsed, total_duration, market.malus_k_millis)?;
+
+    // Calculate spot quadratic uplift and unit price on current total votes
+    let uplift_nanos = calculate_quadratic_uplift(
+        market.total_votes,
+        market.quad_a_micros,
+        market.quad_b_micros,
+    )?;
+    let unit_price = calculate_unit_price(market.base_price_lamports, malus_nanos, uplift_nanos)?;
+
+    // Calculate quadratic uplift summed over every vote bought,
+    // from the current total votes to total votes + vote_qty
+    let uplift_sum_nanos = calculate_quadratic_uplift_sum(
+        market.total_votes,
+        vote_qty,
+        market.quad_a_micros,
+        market.quad_b_micros,
+    )?;
+
+    // Calculate total cost
+    let total_cost =
+        calculate_total_cost(market.base_price_lamports, malus_nanos, uplift_sum_nanos)?;
+
+    Ok(BetQuote {
+        emoji_id,
+        vote_qty,
+        malus_nanos,
+        uplift_nanos,
+        unit_price,
+        total_cost,
+    })
+}
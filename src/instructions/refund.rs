@@ -0,0 +1,115 @@
+use crate::error::ErrorCode;
+use crate::events::Refunded;
+use crate::state::{BetAccount, Config, Market};
+use crate::vault::{
+    require_token_account, require_token_owner, require_vault, transfer_from_vault,
+    transfer_lamports,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct Refund<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), user.key().as_ref()],
+        bump = bet.bump
+    )]
+    pub bet: Account<'info, BetAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token markets only: the user's account receiving the refund
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the market's mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<Refund>, market_id: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let bet = &mut ctx.accounts.bet;
+    let user = &ctx.accounts.user;
+
+    // Require market Cancelled
+    require!(market.status == 2, ErrorCode::MarketNotCancelled);
+
+    // Require claim window still open
+    require!(
+        Clock::get()?.unix_timestamp < market.claim_deadline_ts,
+        ErrorCode::ClaimWindowClosed
+    );
+
+    // Require not yet refunded
+    require!(!bet.claimed, ErrorCode::AlreadyClaimed);
+
+    // Everything the user paid goes back, no fees are taken
+    let amount = bet.total_spent;
+
+    // Transfer from market to user
+    if amount > 0 {
+        match market.mint {
+            Some(mint) => {
+                let user_token_account = require_token_account(&ctx.accounts.user_token_account)?;
+                let mint_account = require_token_account(&ctx.accounts.mint)?;
+                let vault = require_token_account(&ctx.accounts.vault)?;
+                let token_program = require_token_account(&ctx.accounts.token_program)?;
+                require_token_owner(user_token_account, &mint, &user.key())?;
+                require_vault(
+                    &market.key(),
+                    &mint,
+                    mint_account,
+                    &vault.key(),
+                    &token_program.key(),
+                )?;
+                transfer_from_vault(
+                    token_program,
+                    mint_account,
+                    vault,
+                    user_token_account,
+                    market,
+                    market_id,
+                    amount,
+                )?;
+            }
+            None => transfer_lamports(&market.to_account_info(), &user.to_account_info(), amount)?,
+        }
+    }
+
+    // Mark claimed so the refund can't be taken twice
+    bet.claimed = true;
+
+    emit_cpi!(Refunded {
+        market: market.key(),
+        user: user.key(),
+        amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use crate::error::ErrorCode;
+use crate::events::EmojiRegistered;
+use crate::state::{Config, EmojiEntry, MAX_EMOJI_SEQUENCE_LEN};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(emoji_id: u32)]
+pub struct RegisterEmoji<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = EmojiEntry::LEN,
+        seeds = [b"emoji".as_ref(), &emoji_id.to_le_bytes()],
+        bump
+    )]
+    pub emoji: Account<'info, EmojiEntry>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<RegisterEmoji>, emoji_id: u32, sequence: String) -> Result<()> {
+    validate_sequence(&sequence)?;
+
+    let emoji = &mut ctx.accounts.emoji;
+    emoji.emoji_id = emoji_id;
+    emoji.sequence = sequence;
+    emoji.enabled = true;
+    emoji.bump = ctx.bumps.emoji;
+
+    emit_cpi!(EmojiRegistered {
+        emoji_id,
+        sequence: emoji.sequence.clone(),
+    });
+
+    Ok(())
+}
+
+/// Validate an emoji's UTF-8 sequence length
+pub fn validate_sequence(sequence: &str) -> Result<()> {
+    require!(
+        !sequence.is_empty() && sequence.len() <= MAX_EMOJI_SEQUENCE_LEN,
+        ErrorCode::InvalidEmojiSequence
+    );
+
+    Ok(())
+}
+
+/// Check that `info` is the registry entry for `emoji_id` and that it is enabled
+/// Used for ids passed through remaining accounts
+pub fn require_registered(info: &AccountInfo, emoji_id: u32) -> Result<()> {
+    require!(info.owner == &crate::ID, ErrorCode::EmojiNotRegistered);
+
+    let data = info.try_borrow_data()?;
+    let entry = EmojiEntry::try_deserialize(&mut &data[..])
+        .map_err(|_| error!(ErrorCode::EmojiNotRegistered))?;
+
+    require!(entry.emoji_id == emoji_id, ErrorCode::EmojiNotRegistered);
+    require!(entry.enabled, ErrorCode::EmojiDisabled);
+
+    Ok(())
+}
@@ -0,0 +1,162 @@
+use crate::error::ErrorCode;
+use crate::events::VotesSold;
+use crate::instructions::bet::remove_bet_votes;
+use crate::math::{calculate_fee, calculate_sell_value};
+use crate::state::{BetAccount, Config, Market};
+use crate::vault::{
+    require_token_account, require_token_owner, require_vault, transfer_from_vault,
+    transfer_lamports,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct SellVotes<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.bets_paused @ ErrorCode::BetsPaused,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), user.key().as_ref()],
+        bump = bet.bump
+    )]
+    pub bet: Account<'info, BetAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Token markets only: the user's account receiving the refund
+    #[account(mut)]
+    pub user_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token markets only: the market's mint
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token markets only: the market's vault
+    #[account(mut)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<SellVotes>,
+    market_id: u64,
+    emoji_id: u32,
+    vote_qty: u64,
+    min_refund: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let bet = &mut ctx.accounts.bet;
+    let user = &ctx.accounts.user;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(vote_qty >= 1, ErrorCode::InvalidVoteQuantity);
+
+    // Votes can only be sold while the market is trading
+    require!(market.status == 0, ErrorCode::MarketNotActive);
+    require!(now < market.end_ts, ErrorCode::MarketEnded);
+
+    // Take the votes out of the user's position
+    let spent_sold = remove_bet_votes(bet, emoji_id, vote_qty)?;
+    let market_idx = market
+        .emoji_ids
+        .iter()
+        .position(|&id| id == emoji_id)
+        .ok_or(ErrorCode::InsufficientVotes)?;
+
+    // Price the votes at the top of the curve, then keep the platform and
+    // creator spread in the pot for the remaining bettors
+    let value = calculate_sell_value(
+        market.base_price_lamports,
+        market.total_votes,
+        vote_qty,
+        market.quad_a_micros,
+        market.quad_b_micros,
+    )?;
+    let spread_bps = market
+        .platform_fee_bps
+        .checked_add(market.creator_fee_bps)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let spread = calculate_fee(value, spread_bps)?;
+    // Never more than the votes cost: the pot then always covers every
+    // bettor's total_spent, so a cancelled market can still refund them in full
+    let refund = value
+        .checked_sub(spread)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?
+        .min(spent_sold);
+
+    // Price moved below what the user accepted
+    require!(refund >= min_refund, ErrorCode::SlippageExceeded);
+
+    market.emoji_votes[market_idx] = market.emoji_votes[market_idx]
+        .checked_sub(vote_qty)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    market.total_votes = market
+        .total_votes
+        .checked_sub(vote_qty)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    market.total_pot = market
+        .total_pot
+        .checked_sub(refund)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    // Transfer from market to user
+    if refund > 0 {
+        match market.mint {
+            Some(mint) => {
+                let user_token_account = require_token_account(&ctx.accounts.user_token_account)?;
+                let mint_account = require_token_account(&ctx.accounts.mint)?;
+                let vault = require_token_account(&ctx.accounts.vault)?;
+                let token_program = require_token_account(&ctx.accounts.token_program)?;
+                require_token_owner(user_token_account, &mint, &user.key())?;
+                require_vault(
+                    &market.key(),
+                    &mint,
+                    mint_account,
+                    &vault.key(),
+                    &token_program.key(),
+                )?;
+                transfer_from_vault(
+                    token_program,
+                    mint_account,
+                    vault,
+                    user_token_account,
+                    market,
+                    market_id,
+                    refund,
+                )?;
+            }
+            None => transfer_lamports(&market.to_account_info(), &user.to_account_info(), refund)?,
+        }
+    }
+
+    emit_cpi!(VotesSold {
+        market: market.key(),
+        user: user.key(),
+        emoji_id,
+        vote_qty,
+        refund,
+        spread,
+        total_votes: market.total_votes,
+        total_pot: market.total_pot,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::error::ErrorCode;
+use crate::events::AcceptedMintSet;
+use crate::state::{AcceptedMint, Config};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetAcceptedMint<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = AcceptedMint::LEN,
+        seeds = [b"accepted_mint", mint.key().as_ref()],
+        bump
+    )]
+    pub accepted_mint: Account<'info, AcceptedMint>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SetAcceptedMint>, base_price: u64, enabled: bool) -> Result<()> {
+    require!(base_price > 0, ErrorCode::InvalidBasePrice);
+
+    // Existing markets keep the base price they snapshotted
+    let accepted_mint = &mut ctx.accounts.accepted_mint;
+    accepted_mint.mint = ctx.accounts.mint.key();
+    accepted_mint.base_price = base_price;
+    accepted_mint.enabled = enabled;
+    accepted_mint.bump = ctx.bumps.accepted_mint;
+
+    emit_cpi!(AcceptedMintSet {
+        mint: accepted_mint.mint,
+        base_price,
+        enabled,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::error::ErrorCode;
+use crate::events::PauseUpdated;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<SetPause>,
+    markets_paused: bool,
+    bets_paused: bool,
+    withdrawals_paused: bool,
+) -> Result<()> {
+    // Each flag is set explicitly so lifting one pause never touches the others
+    let config = &mut ctx.accounts.config;
+    config.markets_paused = markets_paused;
+    config.bets_paused = bets_paused;
+    config.withdrawals_paused = withdrawals_paused;
+
+    emit_cpi!(PauseUpdated {
+        admin: ctx.accounts.admin.key(),
+        markets_paused,
+        bets_paused,
+        withdrawals_paused,
+    });
+
+    Ok(())
+}
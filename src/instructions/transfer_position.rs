@@ -0,0 +1,88 @@
+use crate::error::ErrorCode;
+use crate::events::PositionTransferred;
+use crate::instructions::bet::{add_bet_votes, init_bet_if_needed, remove_bet_votes};
+use crate::state::{BetAccount, Config, Market};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(market_id: u64)]
+pub struct TransferPosition<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.bets_paused @ ErrorCode::BetsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.creator.as_ref(), &market_id.to_le_bytes()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), user.key().as_ref()],
+        bump = bet.bump
+    )]
+    pub bet: Account<'info, BetAccount>,
+
+    /// Recipient wallet; a system account so its BetAccount can later be
+    /// closed with the rent going back to it
+    pub to: SystemAccount<'info>,
+
+    /// The recipient's position, created on its first transfer
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = BetAccount::space(),
+        seeds = [b"bet", market.key().as_ref(), to.key().as_ref()],
+        bump
+    )]
+    pub to_bet: Account<'info, BetAccount>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<TransferPosition>, emoji_id: u32, vote_qty: u64) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let bet = &mut ctx.accounts.bet;
+    let to_bet = &mut ctx.accounts.to_bet;
+    let user = &ctx.accounts.user;
+    let to = ctx.accounts.to.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(vote_qty >= 1, ErrorCode::InvalidVoteQuantity);
+    require!(to != user.key(), ErrorCode::SelfTransfer);
+
+    // Positions only move while the market is trading
+    require!(market.status == 0, ErrorCode::MarketNotActive);
+    require!(now < market.end_ts, ErrorCode::MarketEnded);
+    require!(!bet.claimed, ErrorCode::AlreadyClaimed);
+
+    init_bet_if_needed(market, to_bet, to, ctx.bumps.to_bet)?;
+
+    // Market totals are unchanged, only the owner of the votes changes
+    let spent_moved = remove_bet_votes(bet, emoji_id, vote_qty)?;
+    add_bet_votes(to_bet, emoji_id, vote_qty)?;
+    to_bet.total_spent = to_bet
+        .total_spent
+        .checked_add(spent_moved)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit_cpi!(PositionTransferred {
+        market: market.key(),
+        from: user.key(),
+        to,
+        emoji_id,
+        vote_qty,
+        spent_moved,
+    });
+
+    Ok(())
+}
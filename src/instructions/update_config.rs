@@ -0,0 +1,70 @@
+use crate::error::ErrorCode;
+use crate::events::ConfigUpdated;
+use crate::instructions::initialize_config::validate_params;
+use crate::state::Config;
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler(
+    ctx: Context<UpdateConfig>,
+    platform_fee_bps: u16,
+    creator_fee_bps: u16,
+    base_price_lamports: u64,
+    malus_k_millis: u32,
+    quad_a_micros: u64,
+    quad_b_micros: u64,
+    min_duration_secs: u32,
+    max_duration_secs: u32,
+    claim_window_secs: u32,
+    end_grace_secs: u32,
+    crank_fee_bps: u16,
+) -> Result<()> {
+    validate_params(
+        platform_fee_bps,
+        creator_fee_bps,
+        base_price_lamports,
+        min_duration_secs,
+        max_duration_secs,
+        claim_window_secs,
+        crank_fee_bps,
+    )?;
+
+    let config = &mut ctx.accounts.config;
+    let old_params = config.params();
+
+    // Existing markets keep their own snapshot of these values
+    config.platform_fee_bps = platform_fee_bps;
+    config.creator_fee_bps = creator_fee_bps;
+    config.base_price_lamports = base_price_lamports;
+    config.malus_k_millis = malus_k_millis;
+    config.quad_a_micros = quad_a_micros;
+    config.quad_b_micros = quad_b_micros;
+    config.min_duration_secs = min_duration_secs;
+    config.max_duration_secs = max_duration_secs;
+    config.claim_window_secs = claim_window_secs;
+    config.end_grace_secs = end_grace_secs;
+    config.crank_fee_bps = crank_fee_bps;
+
+    emit_cpi!(ConfigUpdated {
+        admin: ctx.accounts.admin.key(),
+        old_params,
+        new_params: config.params(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
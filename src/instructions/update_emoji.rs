@@ -0,0 +1,46 @@
+use crate::error::ErrorCode;
+use crate::events::EmojiUpdated;
+use crate::instructions::register_emoji::validate_sequence;
+use crate::state::{Config, EmojiEntry};
+use anchor_lang::prelude::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(emoji_id: u32)]
+pub struct UpdateEmoji<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"emoji".as_ref(), &emoji_id.to_le_bytes()],
+        bump = emoji.bump
+    )]
+    pub emoji: Account<'info, EmojiEntry>,
+
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<UpdateEmoji>, sequence: Option<String>, enabled: bool) -> Result<()> {
+    let emoji = &mut ctx.accounts.emoji;
+
+    if let Some(sequence) = sequence {
+        validate_sequence(&sequence)?;
+        emoji.sequence = sequence;
+    }
+
+    // Disabled ids can't be used in new markets or bets; existing votes still count
+    emoji.enabled = enabled;
+
+    emit_cpi!(EmojiUpdated {
+        emoji_id: emoji.emoji_id,
+        sequence: emoji.sequence.clone(),
+        enabled,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use crate::error::ErrorCode;
+use crate::events::TreasuryWithdrawn;
+use crate::state::{Config, Treasury};
+use crate::vault::{require_token_account, transfer_lamports};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{self, Mint, TokenAccount, TokenInterface, TransferChecked};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin_address == admin.key() @ ErrorCode::Unauthorized,
+        constraint = !config.withdrawals_paused @ ErrorCode::WithdrawalsPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    pub admin: Signer<'info>,
+
+    /// CHECK: Any system account chosen by the admin to receive the fees
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+
+    /// Token fees only: the mint being withdrawn
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token fees only: the treasury's account for that mint
+    #[account(mut, token::authority = treasury)]
+    pub treasury_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token fees only: the account receiving the tokens
+    #[account(mut)]
+    pub destination_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+pub fn handler(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidWithdrawAmount);
+
+    let treasury = &mut ctx.accounts.treasury;
+
+    // Token fees: the whole token balance can be withdrawn
+    if let Some(treasury_token_account) = &ctx.accounts.treasury_token_account {
+        let destination_token_account =
+            require_token_account(&ctx.accounts.destination_token_account)?;
+        let mint = require_token_account(&ctx.accounts.mint)?;
+        let token_program = require_token_account(&ctx.accounts.token_program)?;
+        require!(
+            amount <= treasury_token_account.amount,
+            ErrorCode::InsufficientTreasuryFunds
+        );
+
+        let seeds: &[&[u8]] = &[b"treasury", &[treasury.bump]];
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: treasury_token_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: destination_token_account.to_account_info(),
+                    authority: treasury.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+            mint.decimals,
+        )?;
+
+        emit_cpi!(TreasuryWithdrawn {
+            admin: ctx.accounts.admin.key(),
+            destination: destination_token_account.key(),
+            mint: Some(treasury_token_account.mint),
+            amount,
+            total_withdrawn: treasury.total_withdrawn,
+        });
+
+        return Ok(());
+    }
+
+    let treasury_info = treasury.to_account_info();
+
+    // The treasury keeps its rent so it stays open for future fees
+    let rent_exempt = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(rent_exempt);
+    require!(amount <= available, ErrorCode::InsufficientTreasuryFunds);
+
+    transfer_lamports(&treasury_info, &ctx.accounts.destination, amount)?;
+
+    treasury.total_withdrawn = treasury
+        .total_withdrawn
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    emit_cpi!(TreasuryWithdrawn {
+        admin: ctx.accounts.admin.key(),
+        destination: ctx.accounts.destination.key(),
+        mint: None,
+        amount,
+        total_withdrawn: treasury.total_withdrawn,
+    });
+
+    Ok(())
+}
@@ -1,9 +1,11 @@
 use anchor_lang::prelude::*;
 
 pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod math;
 pub mod state;
+pub mod vault;
 
 use instructions::*;
 
@@ -13,6 +15,7 @@ declare_id!("ZTnFhxro4BiVWvAhH6m11SJx4BUDieP2Vu4yYymco1u");
 pub mod emojimarket_program {
     use super::*;
 
+    #[allow(clippy::too_many_arguments)]
     pub fn initialize_config(
         ctx: Context<InitializeConfig>,
         admin_address: Pubkey,
@@ -24,6 +27,9 @@ pub mod emojimarket_program {
         quad_b_micros: u64,
         min_duration_secs: u32,
         max_duration_secs: u32,
+        claim_window_secs: u32,
+        end_grace_secs: u32,
+        crank_fee_bps: u16,
     ) -> Result<()> {
         instructions::initialize_config::handler(
             ctx,
@@ -36,17 +42,121 @@ pub mod emojimarket_program {
             quad_b_micros,
             min_duration_secs,
             max_duration_secs,
+            claim_window_secs,
+            end_grace_secs,
+            crank_fee_bps,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        platform_fee_bps: u16,
+        creator_fee_bps: u16,
+        base_price_lamports: u64,
+        malus_k_millis: u32,
+        quad_a_micros: u64,
+        quad_b_micros: u64,
+        min_duration_secs: u32,
+        max_duration_secs: u32,
+        claim_window_secs: u32,
+        end_grace_secs: u32,
+        crank_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::update_config::handler(
+            ctx,
+            platform_fee_bps,
+            creator_fee_bps,
+            base_price_lamports,
+            malus_k_millis,
+            quad_a_micros,
+            quad_b_micros,
+            min_duration_secs,
+            max_duration_secs,
+            claim_window_secs,
+            end_grace_secs,
+            crank_fee_bps,
+        )
+    }
+
+    pub fn migrate_config(
+        ctx: Context<MigrateConfig>,
+        claim_window_secs: u32,
+        end_grace_secs: u32,
+        crank_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::migrate_config::handler(ctx, claim_window_secs, end_grace_secs, crank_fee_bps)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    pub fn cancel_admin_transfer(ctx: Context<CancelAdminTransfer>) -> Result<()> {
+        instructions::cancel_admin_transfer::handler(ctx)
+    }
+
+    pub fn set_accepted_mint(
+        ctx: Context<SetAcceptedMint>,
+        base_price: u64,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::set_accepted_mint::handler(ctx, base_price, enabled)
+    }
+
+    pub fn set_pause(
+        ctx: Context<SetPause>,
+        markets_paused: bool,
+        bets_paused: bool,
+        withdrawals_paused: bool,
+    ) -> Result<()> {
+        instructions::set_pause::handler(ctx, markets_paused, bets_paused, withdrawals_paused)
+    }
+
+    pub fn register_emoji(
+        ctx: Context<RegisterEmoji>,
+        emoji_id: u32,
+        sequence: String,
+    ) -> Result<()> {
+        instructions::register_emoji::handler(ctx, emoji_id, sequence)
+    }
+
+    pub fn update_emoji(
+        ctx: Context<UpdateEmoji>,
+        _emoji_id: u32,
+        sequence: Option<String>,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::update_emoji::handler(ctx, sequence, enabled)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn create_market(
         ctx: Context<CreateMarket>,
         market_id: u64,
         title: String,
         image_url: Option<String>,
         end_ts: i64,
+        tie_policy: u8,
+        payout_bps: Vec<u16>,
+        max_emojis: Option<u8>,
+        allowed_emoji_ids: Vec<u32>,
     ) -> Result<()> {
-        instructions::create_market::handler(ctx, market_id, title, image_url, end_ts)
+        instructions::create_market::handler(
+            ctx,
+            market_id,
+            title,
+            image_url,
+            end_ts,
+            tie_policy,
+            payout_bps,
+            max_emojis,
+            allowed_emoji_ids,
+        )
     }
 
     pub fn bet(
@@ -54,15 +164,73 @@ pub mod emojimarket_program {
         _market_id: u64,
         emoji_id: u32,
         vote_qty: u64,
+        max_total_cost: u64,
+    ) -> Result<()> {
+        instructions::bet::handler(ctx, emoji_id, vote_qty, max_total_cost)
+    }
+
+    pub fn bet_many(
+        ctx: Context<BetMany>,
+        _market_id: u64,
+        orders: Vec<BetOrder>,
+        max_total_cost: u64,
+    ) -> Result<()> {
+        instructions::bet_many::handler(ctx, orders, max_total_cost)
+    }
+
+    pub fn sell_votes(
+        ctx: Context<SellVotes>,
+        market_id: u64,
+        emoji_id: u32,
+        vote_qty: u64,
+        min_refund: u64,
+    ) -> Result<()> {
+        instructions::sell_votes::handler(ctx, market_id, emoji_id, vote_qty, min_refund)
+    }
+
+    pub fn transfer_position(
+        ctx: Context<TransferPosition>,
+        _market_id: u64,
+        emoji_id: u32,
+        vote_qty: u64,
     ) -> Result<()> {
-        instructions::bet::handler(ctx, emoji_id, vote_qty)
+        instructions::transfer_position::handler(ctx, emoji_id, vote_qty)
+    }
+
+    pub fn quote_bet(
+        ctx: Context<QuoteBet>,
+        _market_id: u64,
+        emoji_id: u32,
+        vote_qty: u64,
+    ) -> Result<BetQuote> {
+        instructions::quote_bet::handler(ctx, emoji_id, vote_qty)
+    }
+
+    pub fn end_market(ctx: Context<EndMarket>, market_id: u64) -> Result<()> {
+        instructions::end_market::handler(ctx, market_id)
+    }
+
+    pub fn claim(ctx: Context<Claim>, market_id: u64) -> Result<()> {
+        instructions::claim::handler(ctx, market_id)
+    }
+
+    pub fn close_bet(ctx: Context<CloseBet>, _market_id: u64) -> Result<()> {
+        instructions::close_bet::handler(ctx)
+    }
+
+    pub fn close_market(ctx: Context<CloseMarket>, market_id: u64) -> Result<()> {
+        instructions::close_market::handler(ctx, market_id)
+    }
+
+    pub fn cancel_market(ctx: Context<CancelMarket>, _market_id: u64) -> Result<()> {
+        instructions::cancel_market::handler(ctx)
     }
 
-    pub fn end_market(ctx: Context<EndMarket>, _market_id: u64) -> Result<()> {
-        instructions::end_market::handler(ctx)
+    pub fn refund(ctx: Context<Refund>, market_id: u64) -> Result<()> {
+        instructions::refund::handler(ctx, market_id)
     }
 
-    pub fn claim(ctx: Context<Claim>, _market_id: u64) -> Result<()> {
-        instructions::claim::handler(ctx)
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury::handler(ctx, amount)
     }
 }
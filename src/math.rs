@@ -3,7 +3,7 @@ use anchor_lang::prelude::*;
 
 /// Calculate the malus factor: exp((k * x) / (1 - x)) - 1
 /// Where x is time progress [0, 1) and k = malus_k_millis / 1000
-/// Returns the malus multiplied by 1e9 for precision
+/// Returns the malus multiplied by 1e9 for precision, capped at `MAX_MALUS_NANOS`
 pub fn calculate_malus(
     elapsed_secs: i64,
     total_duration_secs: i64,
@@ -22,7 +22,7 @@ pub fn calculate_malus(
 
     // If x >= 1, that means market ended, return max malus
     if x_nanos >= 1_000_000_000 {
-        return Ok(1_000_000_000_000); // Very high malus
+        return Ok(MAX_MALUS_NANOS);
     }
 
     // Calculate (1 - x) in nanos
@@ -31,7 +31,7 @@ pub fn calculate_malus(
         .ok_or(ErrorCode::ArithmeticUnderflow)?;
 
     if one_minus_x == 0 {
-        return Ok(1_000_000_000_000); // Very high malus
+        return Ok(MAX_MALUS_NANOS);
     }
 
     // Calculate k * x where k = malus_k_millis / 1000
@@ -41,70 +41,90 @@ pub fn calculate_malus(
         .checked_mul(x_nanos as u128)
         .ok_or(ErrorCode::ArithmeticOverflow)?
         .checked_div(1000)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
+        .ok_or(ErrorCode::DivisionByZero)?;
 
     // Calculate (k * x) / (1 - x)
-    let exponent = (k_x_nanos as u128)
+    // Grows without bound as x -> 1, an exponent past u64 saturates the malus anyway
+    let exponent = k_x_nanos
         .checked_mul(1_000_000_000)
         .ok_or(ErrorCode::ArithmeticOverflow)?
         .checked_div(one_minus_x as u128)
-        .ok_or(ErrorCode::DivisionByZero)? as u64;
+        .ok_or(ErrorCode::DivisionByZero)?;
+    let exponent = u64::try_from(exponent).unwrap_or(u64::MAX);
 
-    // Approximate exp(exponent) - 1 using Taylor series
-    // exp(y) ≈ 1 + y + y²/2! + y³/3! + y⁴/4! + ...
-    // We'll use first 5 terms for reasonable accuracy
-    let malus = exp_taylor_minus_one(exponent)?;
+    let malus = exp_minus_one(exponent)?;
 
     Ok(malus)
 }
 
-/// Approximate exp(x) - 1 using Taylor series where x is in nanos (x / 1e9)
-/// Returns result * 1e9
-fn exp_taylor_minus_one(x_nanos: u64) -> Result<u64> {
-    // For small x: exp(x) - 1 ≈ x + x²/2 + x³/6 + x⁴/24 + x⁵/120
-    // x is in nanos, so we need to be careful with scaling
+/// Cap on the malus: 1000 (times 1e9), i.e. a unit price of at most 1001x the
+/// base price. Also returned once the market's time has run out (x >= 1)
+pub const MAX_MALUS_NANOS: u64 = 1_000_000_000_000;
 
-    let x = x_nanos as u128;
-    let scale = 1_000_000_000u128;
+/// Internal fixed point for `exp_minus_one` (1e18)
+const EXP_SCALE: u128 = 1_000_000_000_000_000_000;
 
-    // First term: x
-    let mut result = x;
+/// ln(2) * 1e18, rounded down
+const LN2_E18: u128 = 693_147_180_559_945_309;
 
-    // Second term: x²/2
-    let x2 = x.checked_mul(x).ok_or(ErrorCode::ArithmeticOverflow)?;
-    let term2 = x2.checked_div(scale * 2).ok_or(ErrorCode::DivisionByZero)?;
-    result = result
-        .checked_add(term2)
-        .ok_or(ErrorCode::ArithmeticOverflow)?;
+/// Exponents at or above ln(1001) ≈ 6.9088 always hit the cap; anything from
+/// 7.0 up short-circuits before range reduction
+const EXP_SATURATION_NANOS: u64 = 7_000_000_000;
 
-    // Third term: x³/6
-    let x3 = x2.checked_mul(x).ok_or(ErrorCode::ArithmeticOverflow)?;
-    let term3 = x3
-        .checked_div(scale * scale * 6)
-        .ok_or(ErrorCode::DivisionByZero)?;
-    result = result
-        .checked_add(term3)
+/// Compute exp(x) - 1 where x is in nanos (x / 1e9), saturating at `MAX_MALUS_NANOS`
+/// Returns result * 1e9
+///
+/// Range reduction: x = k*ln(2) + r with 0 <= r < ln(2), so exp(x) = 2^k * exp(r).
+/// exp(r) comes from its Taylor series at 1e18 precision, summed until the
+/// next term rounds to zero (at most ~20 terms since r < 0.7).
+///
+/// Error bound: below the cap the result is within 1 (i.e. 1e-9) of the
+/// floored exact value. The series truncation and per-term rounding stay under
+/// 1e-16 at 1e18 scale, times 2^k <= 2^10 is still well under one nano before the
+/// final rounding down to 1e9 scale.
+pub fn exp_minus_one(x_nanos: u64) -> Result<u64> {
+    if x_nanos >= EXP_SATURATION_NANOS {
+        return Ok(MAX_MALUS_NANOS);
+    }
+
+    // Move x to 1e18 scale and split off multiples of ln(2)
+    let x = (x_nanos as u128)
+        .checked_mul(1_000_000_000)
         .ok_or(ErrorCode::ArithmeticOverflow)?;
+    let k = x.checked_div(LN2_E18).ok_or(ErrorCode::DivisionByZero)?;
+    let r = x
+        .checked_sub(k * LN2_E18)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
 
-    // Fourth term: x⁴/24
-    let x4 = x3.checked_mul(x).ok_or(ErrorCode::ArithmeticOverflow)?;
-    let term4 = x4
-        .checked_div(scale * scale * scale * 24)
-        .ok_or(ErrorCode::DivisionByZero)?;
-    result = result
-        .checked_add(term4)
+    // exp(r) = Σ r^i / i!, each term built from the previous one
+    let mut exp_r = EXP_SCALE;
+    let mut term = EXP_SCALE;
+    let mut i = 1u128;
+    while term > 0 {
+        term = term
+            .checked_mul(r)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(i * EXP_SCALE)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        exp_r = exp_r
+            .checked_add(term)
+            .ok_or(ErrorCode::ArithmeticOverflow)?;
+        i += 1;
+    }
+
+    // exp(x) = exp(r) * 2^k, k <= 10 below the saturation threshold
+    let exp_x = exp_r
+        .checked_shl(k as u32)
         .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-    // Fifth term: x⁵/120
-    let x5 = x4.checked_mul(x).ok_or(ErrorCode::ArithmeticOverflow)?;
-    let term5 = x5
-        .checked_div(scale * scale * scale * scale * 120)
+    // Back to 1e9 scale, minus one
+    let result = exp_x
+        .checked_sub(EXP_SCALE)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?
+        .checked_div(1_000_000_000)
         .ok_or(ErrorCode::DivisionByZero)?;
-    result = result
-        .checked_add(term5)
-        .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-    Ok(result as u64)
+    Ok(result.min(MAX_MALUS_NANOS as u128) as u64)
 }
 
 /// Calculate quadratic uplift: f(n) = 1 + a*n + b*n²
@@ -182,13 +202,135 @@ pub fn calculate_unit_price(
     Ok(final_price as u64)
 }
 
-/// Calculate total cost for vote_qty votes, rounding up
-pub fn calculate_total_cost(unit_price: u64, vote_qty: u64) -> Result<u64> {
-    let total = (unit_price as u128)
-        .checked_mul(vote_qty as u128)
+/// Sum the quadratic uplift over every vote of an order:
+/// sum of f(i) = 1 + a*i + b*i² for i in [n, n + vote_qty)
+/// Closed form: vote_qty + a * Σi + b * Σi², so a large order pays the curve
+/// exactly as if it were split into single-vote bets
+/// Returns the summed multiplier * 1e9 for precision
+pub fn calculate_quadratic_uplift_sum(
+    n: u64,
+    vote_qty: u64,
+    quad_a_micros: u64,
+    quad_b_micros: u64,
+) -> Result<u128> {
+    let n_128 = n as u128;
+    let q = vote_qty as u128;
+    let scale = 1_000_000_000u128;
+
+    // Σ 1 over the range, in our fixed point (1e9)
+    let mut result = q.checked_mul(scale).ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    // Σi for i in [n, n + q) = q*n + q(q-1)/2
+    let sum_i = q
+        .checked_mul(n_128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_add(q * q.saturating_sub(1) / 2)
         .ok_or(ErrorCode::ArithmeticOverflow)?;
 
-    Ok(total as u64)
+    // Σi² for i in [n, n + q) = S(n + q) - S(n), with S(m) = Σi² for i < m
+    let end = n_128.checked_add(q).ok_or(ErrorCode::ArithmeticOverflow)?;
+    let sum_i2 = sum_of_squares_below(end)?
+        .checked_sub(sum_of_squares_below(n_128)?)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    // Add a*Σi term: (quad_a_micros * Σi) / 1e6
+    let a_sum = (quad_a_micros as u128)
+        .checked_mul(sum_i)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_mul(1000) // Convert from micros (1e6) to nanos (1e9)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(1_000_000)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    result = result
+        .checked_add(a_sum)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    // Add b*Σi² term: (quad_b_micros * Σi²) / 1e6
+    let b_sum = (quad_b_micros as u128)
+        .checked_mul(sum_i2)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_mul(1000) // Convert from micros to nanos
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(1_000_000)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    result = result
+        .checked_add(b_sum)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(result)
+}
+
+/// Σi² for i in [0, m) = (m-1) * m * (2m-1) / 6
+fn sum_of_squares_below(m: u128) -> Result<u128> {
+    if m == 0 {
+        return Ok(0);
+    }
+
+    let sum = (m - 1)
+        .checked_mul(m)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_mul(2 * m - 1)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(6)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    Ok(sum)
+}
+
+/// Calculate the total cost of an order
+/// total_cost = base_price_lamports * (1 + malus) * Σf(i)
+/// malus is in nanos, the uplift sum comes from `calculate_quadratic_uplift_sum`
+/// Rounds down once on the whole order, so it is never below the sum of
+/// single-vote unit prices
+pub fn calculate_total_cost(
+    base_price_lamports: u64,
+    malus_nanos: u64,
+    uplift_sum_nanos: u128,
+) -> Result<u64> {
+    let base = base_price_lamports as u128;
+    let scale = 1_000_000_000u128;
+
+    // Calculate (1 + malus) = (1e9 + malus_nanos)
+    let one_plus_malus = scale
+        .checked_add(malus_nanos as u128)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    // Multiply by base_price
+    let price_with_malus = base
+        .checked_mul(one_plus_malus)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(scale)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    // Multiply by summed quadratic factor
+    let total = price_with_malus
+        .checked_mul(uplift_sum_nanos)
+        .ok_or(ErrorCode::ArithmeticOverflow)?
+        .checked_div(scale)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    Ok(u64::try_from(total).map_err(|_| ErrorCode::ArithmeticOverflow)?)
+}
+
+/// Value of the last `vote_qty` votes on the curve, used to sell them back:
+/// base_price_lamports * Σf(i) for i in [total_votes - vote_qty, total_votes)
+/// The inverse of a buy at the current total, without the time malus
+pub fn calculate_sell_value(
+    base_price_lamports: u64,
+    total_votes: u64,
+    vote_qty: u64,
+    quad_a_micros: u64,
+    quad_b_micros: u64,
+) -> Result<u64> {
+    let n = total_votes
+        .checked_sub(vote_qty)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+    let uplift_sum_nanos =
+        calculate_quadratic_uplift_sum(n, vote_qty, quad_a_micros, quad_b_micros)?;
+
+    calculate_total_cost(base_price_lamports, 0, uplift_sum_nanos)
 }
 
 /// Calculate fee amount from total using basis points
@@ -202,6 +344,70 @@ pub fn calculate_fee(total: u64, fee_bps: u16) -> Result<u64> {
     Ok(fee as u64)
 }
 
+/// Rank emojis by votes (highest first, ties by lowest emoji_id) and split the
+/// payout pool across the top places using `payout_bps` weights (1st, 2nd, ...)
+///
+/// With `split_ties`, emojis tied on votes pool the weights of the places they
+/// occupy and share them evenly; otherwise each takes its own place in id order.
+/// Emojis without votes never place, and when fewer emojis place than there are
+/// tiers the used weights are scaled up so the whole pool is paid out.
+/// Returns winning emoji ids with their payout in rank order, rounding down.
+pub fn calculate_tier_payouts(
+    emoji_ids: &[u32],
+    emoji_votes: &[u64],
+    payout_bps: &[u16],
+    split_ties: bool,
+    payout_pool: u64,
+) -> Result<(Vec<u32>, Vec<u64>)> {
+    let mut ranked: Vec<(u32, u64)> = emoji_ids
+        .iter()
+        .copied()
+        .zip(emoji_votes.iter().copied())
+        .filter(|&(_, votes)| votes > 0)
+        .collect();
+    ranked.sort_unstable_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    // Group emojis that share a payout: tied runs when splitting, singletons otherwise
+    let mut groups: Vec<(&[(u32, u64)], u64)> = Vec::new();
+    let mut place = 0;
+    while place < ranked.len() && place < payout_bps.len() {
+        let mut end = place + 1;
+        if split_ties {
+            while end < ranked.len() && ranked[end].1 == ranked[place].1 {
+                end += 1;
+            }
+        }
+
+        let weight: u64 = payout_bps[place..end.min(payout_bps.len())]
+            .iter()
+            .map(|&bps| bps as u64)
+            .sum();
+        groups.push((&ranked[place..end], weight));
+        place = end;
+    }
+
+    let total_weight: u64 = groups.iter().map(|&(_, weight)| weight).sum();
+
+    let mut winners = Vec::new();
+    let mut payouts = Vec::new();
+    for (members, weight) in groups {
+        let group_pool = (payout_pool as u128)
+            .checked_mul(weight as u128)
+            .ok_or(ErrorCode::ArithmeticOverflow)?
+            .checked_div(total_weight as u128)
+            .ok_or(ErrorCode::DivisionByZero)?
+            .checked_div(members.len() as u128)
+            .ok_or(ErrorCode::DivisionByZero)?;
+
+        for &(emoji_id, _) in members {
+            winners.push(emoji_id);
+            payouts.push(group_pool as u64);
+        }
+    }
+
+    Ok((winners, payouts))
+}
+
 /// Calculate user's share of payout pool using 128-bit intermediates
 pub fn calculate_user_share(
     payout_pool: u64,
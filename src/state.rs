@@ -11,6 +11,13 @@ pub struct Config {
     pub quad_b_micros: u64,
     pub min_duration_secs: u32,
     pub max_duration_secs: u32,
+    pub claim_window_secs: u32,
+    pub end_grace_secs: u32,
+    pub crank_fee_bps: u16,
+    pub markets_paused: bool,
+    pub bets_paused: bool,
+    pub withdrawals_paused: bool,
+    pub pending_admin: Option<Pubkey>,
     pub bump: u8,
 }
 
@@ -25,15 +32,70 @@ impl Config {
         8 + // quad_b_micros
         4 + // min_duration_secs
         4 + // max_duration_secs
+        4 + // claim_window_secs
+        4 + // end_grace_secs
+        2 + // crank_fee_bps
+        1 + // markets_paused
+        1 + // bets_paused
+        1 + // withdrawals_paused
+        1 + 32 + // pending_admin (Option<Pubkey>)
         1; // bump
+
+    pub fn params(&self) -> ConfigParams {
+        ConfigParams {
+            platform_fee_bps: self.platform_fee_bps,
+            creator_fee_bps: self.creator_fee_bps,
+            base_price_lamports: self.base_price_lamports,
+            malus_k_millis: self.malus_k_millis,
+            quad_a_micros: self.quad_a_micros,
+            quad_b_micros: self.quad_b_micros,
+            min_duration_secs: self.min_duration_secs,
+            max_duration_secs: self.max_duration_secs,
+            claim_window_secs: self.claim_window_secs,
+            end_grace_secs: self.end_grace_secs,
+            crank_fee_bps: self.crank_fee_bps,
+        }
+    }
+}
+
+/// Pricing, fee and duration parameters held by `Config`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ConfigParams {
+    pub platform_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub base_price_lamports: u64,
+    pub malus_k_millis: u32,
+    pub quad_a_micros: u64,
+    pub quad_b_micros: u64,
+    pub min_duration_secs: u32,
+    pub max_duration_secs: u32,
+    pub claim_window_secs: u32,
+    pub end_grace_secs: u32,
+    pub crank_fee_bps: u16,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 pub enum MarketStatus {
     Active = 0,
     Ended = 1,
+    Cancelled = 2,
+}
+
+/// How `end_market` resolves emojis tied for the most votes
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TiePolicy {
+    /// Only the lowest emoji_id wins
+    LowestId = 0,
+    /// The payout pool is split evenly across all tied emojis
+    Split = 1,
 }
 
+/// Maximum number of distinct emojis in a market (and so in a bet)
+pub const MAX_EMOJIS: usize = 50;
+
+/// Maximum number of paid places in a market's payout schedule
+pub const MAX_PAYOUT_TIERS: usize = 10;
+
 #[account]
 pub struct Market {
     pub creator: Pubkey,
@@ -46,7 +108,12 @@ pub struct Market {
     pub total_votes: u64,
     pub emoji_ids: Vec<u32>,
     pub emoji_votes: Vec<u64>,
-    pub winner: Option<u32>,
+    pub max_emojis: u8,
+    pub restricted_emojis: bool,
+    pub tie_policy: u8,
+    pub payout_bps: Vec<u16>,
+    pub winners: Vec<u32>,
+    pub winner_payouts: Vec<u64>,
     pub platform_fee_taken: u64,
     pub creator_fee_taken: u64,
     // Snapshot of pricing/fee params from Config
@@ -58,6 +125,15 @@ pub struct Market {
     pub creator_fee_bps: u16,
     pub min_duration_secs: u32,
     pub max_duration_secs: u32,
+    pub claim_window_secs: u32,
+    pub end_grace_secs: u32,
+    pub crank_fee_bps: u16,
+    // Set when the market ends or is cancelled; claims and refunds close then
+    pub claim_deadline_ts: i64,
+    // BetAccounts not yet closed; the market can only be closed once this is 0
+    pub open_bets: u32,
+    // SPL mint the market is denominated in, None for SOL
+    pub mint: Option<Pubkey>,
     pub bump: u8,
 }
 
@@ -71,9 +147,14 @@ impl Market {
         1 + // status
         8 + // total_pot
         8 + // total_votes
-        4 + (4 * 50) + // emoji_ids (max 50 emojis)
-        4 + (8 * 50) + // emoji_votes (max 50 emojis)
-        1 + 4 + // winner (Option<u32>)
+        4 + (4 * MAX_EMOJIS) + // emoji_ids
+        4 + (8 * MAX_EMOJIS) + // emoji_votes
+        1 + // max_emojis
+        1 + // restricted_emojis
+        1 + // tie_policy
+        4 + (2 * MAX_PAYOUT_TIERS) + // payout_bps
+        4 + (4 * MAX_EMOJIS) + // winners
+        4 + (8 * MAX_EMOJIS) + // winner_payouts
         8 + // platform_fee_taken
         8 + // creator_fee_taken
         8 + // base_price_lamports
@@ -84,6 +165,12 @@ impl Market {
         2 + // creator_fee_bps
         4 + // min_duration_secs
         4 + // max_duration_secs
+        4 + // claim_window_secs
+        4 + // end_grace_secs
+        2 + // crank_fee_bps
+        8 + // claim_deadline_ts
+        4 + // open_bets
+        1 + 32 + // mint
         1; // bump
 
     pub fn space(title_len: usize, image_url_len: usize) -> usize {
@@ -96,9 +183,14 @@ impl Market {
         1 + // status
         8 + // total_pot
         8 + // total_votes
-        4 + (4 * 50) + // emoji_ids (max 50 emojis)
-        4 + (8 * 50) + // emoji_votes (max 50 emojis)
-        1 + 4 + // winner
+        4 + (4 * MAX_EMOJIS) + // emoji_ids
+        4 + (8 * MAX_EMOJIS) + // emoji_votes
+        1 + // max_emojis
+        1 + // restricted_emojis
+        1 + // tie_policy
+        4 + (2 * MAX_PAYOUT_TIERS) + // payout_bps
+        4 + (4 * MAX_EMOJIS) + // winners
+        4 + (8 * MAX_EMOJIS) + // winner_payouts
         8 + // platform_fee_taken
         8 + // creator_fee_taken
         8 + // base_price_lamports
@@ -109,6 +201,12 @@ impl Market {
         2 + // creator_fee_bps
         4 + // min_duration_secs
         4 + // max_duration_secs
+        4 + // claim_window_secs
+        4 + // end_grace_secs
+        2 + // crank_fee_bps
+        8 + // claim_deadline_ts
+        4 + // open_bets
+        1 + 32 + // mint
         1 // bump
     }
 }
@@ -128,8 +226,8 @@ impl BetAccount {
     pub const BASE_LEN: usize = 8 + // discriminator
         32 + // market
         32 + // user
-        4 + (4 * 50) + // emoji_ids (max 50 emojis)
-        4 + (8 * 50) + // emoji_votes (max 50 emojis)
+        4 + (4 * MAX_EMOJIS) + // emoji_ids
+        4 + (8 * MAX_EMOJIS) + // emoji_votes
         8 + // total_spent
         1 + // claimed
         1; // bump
@@ -138,3 +236,58 @@ impl BetAccount {
         Self::BASE_LEN
     }
 }
+
+/// Maximum UTF-8 length of an emoji sequence (ZWJ sequences, skin tones, flags)
+pub const MAX_EMOJI_SEQUENCE_LEN: usize = 32;
+
+/// Registry entry mapping an emoji_id to its Unicode sequence, managed by the admin
+#[account]
+pub struct EmojiEntry {
+    pub emoji_id: u32,
+    pub sequence: String,
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl EmojiEntry {
+    pub const LEN: usize = 8 + // discriminator
+        4 + // emoji_id
+        4 + MAX_EMOJI_SEQUENCE_LEN + // sequence
+        1 + // enabled
+        1; // bump
+}
+
+/// Program-owned vault accumulating platform fees until the admin withdraws them
+#[account]
+pub struct Treasury {
+    // Lifetime SOL totals; token fees sit in the treasury's token accounts
+    pub total_collected: u64,
+    pub total_withdrawn: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + // discriminator
+        8 + // total_collected
+        8 + // total_withdrawn
+        1; // bump
+}
+
+/// Admin-approved SPL mint that markets can be denominated in
+#[account]
+pub struct AcceptedMint {
+    pub mint: Pubkey,
+    /// Base price per vote in the mint's smallest unit, used instead of
+    /// `Config::base_price_lamports` for markets in this mint
+    pub base_price: u64,
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl AcceptedMint {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint
+        8 + // base_price
+        1 + // enabled
+        1; // bump
+}
@@ -0,0 +1,203 @@
+// Moving market funds: SOL markets hold the pot in the market account's
+// own lamports, token markets in a token vault owned by the market PDA.
+// Token markets go through the token interface, so both the Token and
+// Token-2022 programs are supported.
+
+use crate::error::ErrorCode;
+use crate::state::Market;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions},
+};
+use anchor_spl::token_2022_extensions::{
+    harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint,
+};
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+/// Move lamports out of a program-owned account
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    **from.try_borrow_mut_lamports()? = from
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(ErrorCode::ArithmeticUnderflow)?;
+
+    **to.try_borrow_mut_lamports()? = to
+        .lamports()
+        .checked_add(amount)
+        .ok_or(ErrorCode::ArithmeticOverflow)?;
+
+    Ok(())
+}
+
+/// Unwrap an optional account that a token market can't do without
+pub fn require_token_account<T>(account: &Option<T>) -> Result<&T> {
+    account
+        .as_ref()
+        .ok_or_else(|| error!(ErrorCode::MissingTokenAccounts))
+}
+
+/// Check a token account holds `mint` and belongs to `owner`
+pub fn require_token_owner(account: &TokenAccount, mint: &Pubkey, owner: &Pubkey) -> Result<()> {
+    require!(
+        account.mint == *mint && account.owner == *owner,
+        ErrorCode::InvalidTokenAccount
+    );
+
+    Ok(())
+}
+
+/// Check `mint` is the market's mint and `vault` its associated token account
+pub fn require_vault(
+    market: &Pubkey,
+    market_mint: &Pubkey,
+    mint: &InterfaceAccount<Mint>,
+    vault: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<()> {
+    require_keys_eq!(mint.key(), *market_mint, ErrorCode::InvalidTokenAccount);
+    require_keys_eq!(
+        *vault,
+        get_associated_token_address_with_program_id(market, market_mint, token_program),
+        ErrorCode::InvalidTokenAccount
+    );
+
+    Ok(())
+}
+
+/// Run a CPI signed by the market PDA
+fn with_market_signer<R>(
+    market: &Account<Market>,
+    market_id: u64,
+    f: impl FnOnce(&[&[&[u8]]]) -> Result<R>,
+) -> Result<R> {
+    let market_id_bytes = market_id.to_le_bytes();
+    let seeds: &[&[u8]] = &[
+        b"market",
+        market.creator.as_ref(),
+        &market_id_bytes,
+        &[market.bump],
+    ];
+    f(&[seeds])
+}
+
+/// Pay `amount` into a token market's vault and return what the vault
+/// actually received, which is less than `amount` for transfer-fee mints
+pub fn transfer_to_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    from: &InterfaceAccount<'info, TokenAccount>,
+    vault: &mut InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<u64> {
+    let balance_before = vault.amount;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: from.to_account_info(),
+                mint: mint.to_account_info(),
+                to: vault.to_account_info(),
+                authority: authority.clone(),
+            },
+        ),
+        amount,
+        mint.decimals,
+    )?;
+
+    vault.reload()?;
+    vault
+        .amount
+        .checked_sub(balance_before)
+        .ok_or_else(|| error!(ErrorCode::ArithmeticUnderflow))
+}
+
+/// Pay `amount` out of a token market's vault, signed by the market PDA
+pub fn transfer_from_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    market: &Account<'info, Market>,
+    market_id: u64,
+    amount: u64,
+) -> Result<()> {
+    with_market_signer(market, market_id, |signer_seeds| {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                TransferChecked {
+                    from: vault.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+            mint.decimals,
+        )
+    })
+}
+
+/// Move transfer fees withheld in a Token-2022 vault to its mint; the vault
+/// can't be closed while it still holds any
+pub fn harvest_vault_fees<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+) -> Result<()> {
+    if token_program.key() != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let vault_info = vault.to_account_info();
+    let withheld = {
+        let data = vault_info.try_borrow_data()?;
+        let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+        state
+            .get_extension::<TransferFeeAmount>()
+            .map(|fee| u64::from(fee.withheld_amount))
+            .unwrap_or(0)
+    };
+    if withheld == 0 {
+        return Ok(());
+    }
+
+    harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program.to_account_info(),
+                mint: mint.to_account_info(),
+            },
+        ),
+        vec![vault_info],
+    )
+}
+
+/// Close an emptied vault, its rent going to `destination`
+pub fn close_vault<'info>(
+    token_program: &Interface<'info, TokenInterface>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    market: &Account<'info, Market>,
+    market_id: u64,
+) -> Result<()> {
+    with_market_signer(market, market_id, |signer_seeds| {
+        token_interface::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: vault.to_account_info(),
+                destination: destination.clone(),
+                authority: market.to_account_info(),
+            },
+            signer_seeds,
+        ))
+    })
+}
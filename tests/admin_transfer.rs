@@ -0,0 +1,173 @@
+// Processor tests for the two-step admin transfer
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::Config;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn propose_admin_ix(setup: &Setup, admin: Pubkey, new_admin: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::ProposeAdmin {
+            config: setup.config,
+            admin,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::ProposeAdmin { new_admin }.data(),
+    }
+}
+
+fn accept_admin_ix(setup: &Setup, new_admin: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::AcceptAdmin {
+            config: setup.config,
+            new_admin,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::AcceptAdmin {}.data(),
+    }
+}
+
+fn cancel_admin_transfer_ix(setup: &Setup, admin: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::CancelAdminTransfer {
+            config: setup.config,
+            admin,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::CancelAdminTransfer {}.data(),
+    }
+}
+
+async fn config_state(setup: &mut Setup) -> Config {
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.config)
+        .await
+        .unwrap()
+        .unwrap();
+    Config::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+/// Funded wallet proposed as the next admin
+async fn setup_proposed_admin() -> (Setup, Keypair) {
+    let mut setup = setup_open_market().await;
+    let admin = setup.admin.insecure_clone();
+    let new_admin = Keypair::new();
+    fund(&mut setup.context, &new_admin.pubkey(), 1_000_000_000).await;
+
+    let ix = propose_admin_ix(&setup, admin.pubkey(), new_admin.pubkey());
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    (setup, new_admin)
+}
+
+#[tokio::test]
+async fn test_propose_admin_is_admin_only() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+
+    let ix = propose_admin_ix(&setup, creator.pubkey(), creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+    assert_eq!(config_state(&mut setup).await.pending_admin, None);
+    println!("✅ Only the admin can propose a new admin");
+}
+
+#[tokio::test]
+async fn test_accept_admin_requires_pending_admin() {
+    let (mut setup, new_admin) = setup_proposed_admin().await;
+    let creator = setup.creator.insecure_clone();
+
+    let ix = accept_admin_ix(&setup, creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::NotPendingAdmin);
+
+    let ix = accept_admin_ix(&setup, new_admin.pubkey());
+    send(&mut setup.context, ix, &[&new_admin]).await.unwrap();
+    let config = config_state(&mut setup).await;
+    assert_eq!(config.admin_address, new_admin.pubkey());
+    assert_eq!(config.pending_admin, None);
+    println!("✅ Only the proposed key can accept");
+}
+
+#[tokio::test]
+async fn test_cancel_admin_transfer_clears_pending_admin() {
+    let (mut setup, new_admin) = setup_proposed_admin().await;
+    let admin = setup.admin.insecure_clone();
+    assert_eq!(
+        config_state(&mut setup).await.pending_admin,
+        Some(new_admin.pubkey())
+    );
+
+    let ix = cancel_admin_transfer_ix(&setup, admin.pubkey());
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    let config = config_state(&mut setup).await;
+    assert_eq!(config.pending_admin, None);
+    assert_eq!(config.admin_address, admin.pubkey());
+
+    let ix = accept_admin_ix(&setup, new_admin.pubkey());
+    let result = send(&mut setup.context, ix, &[&new_admin]).await;
+    assert_custom_error(result, ErrorCode::NoPendingAdmin);
+    println!("✅ A cancelled proposal can't be accepted");
+}
+
+#[tokio::test]
+async fn test_only_accepted_admin_has_admin_rights() {
+    let (mut setup, new_admin) = setup_proposed_admin().await;
+    let admin = setup.admin.insecure_clone();
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    // A pending admin has no rights yet
+    let ix = cancel_market_ix(&setup, new_admin.pubkey());
+    let result = send(&mut setup.context, ix, &[&new_admin]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+
+    let ix = accept_admin_ix(&setup, new_admin.pubkey());
+    send(&mut setup.context, ix, &[&new_admin]).await.unwrap();
+
+    // The former admin is treated like anyone else: a keeper during the grace period
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let creator = setup.creator.pubkey();
+    let ix = end_market_ix(&setup, admin.pubkey(), creator);
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::GracePeriodActive);
+    let ix = cancel_market_ix(&setup, admin.pubkey());
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+
+    let ix = end_market_ix(&setup, new_admin.pubkey(), creator);
+    send(&mut setup.context, ix, &[&new_admin]).await.unwrap();
+    println!("✅ Admin rights move with the accepted transfer");
+}
+
+#[tokio::test]
+async fn test_new_admin_can_cancel_market_with_bets() {
+    let (mut setup, new_admin) = setup_proposed_admin().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = accept_admin_ix(&setup, new_admin.pubkey());
+    send(&mut setup.context, ix, &[&new_admin]).await.unwrap();
+
+    let ix = cancel_market_ix(&setup, new_admin.pubkey());
+    send(&mut setup.context, ix, &[&new_admin]).await.unwrap();
+    println!("✅ The new admin can cancel markets");
+}
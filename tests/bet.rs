@@ -0,0 +1,141 @@
+// Processor tests for bet validation against the market and the emoji registry
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::EmojiEntry;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+const UNREGISTERED_EMOJI_ID: u32 = 128_512;
+
+fn update_emoji_ix(
+    setup: &Setup,
+    admin: Pubkey,
+    emoji_id: u32,
+    sequence: Option<&str>,
+    enabled: bool,
+) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::UpdateEmoji {
+            config: setup.config,
+            emoji: emoji_pda(emoji_id),
+            admin,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::UpdateEmoji {
+            _emoji_id: emoji_id,
+            sequence: sequence.map(str::to_string),
+            enabled,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_bet_rejects_new_emoji_past_market_cap() {
+    let mut setup = setup_open_market_with_choices(Some(1), Vec::new()).await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let ix = bet_on_ix(&setup, user.pubkey(), OTHER_EMOJI_ID, 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::TooManyEmojis);
+
+    // Emojis already in the market can still be backed
+    let ix = bet_ix(&setup, 2);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    println!("✅ bet stops at the market's emoji cap");
+}
+
+#[tokio::test]
+async fn test_bet_rejects_emoji_outside_choices() {
+    let mut setup = setup_open_market_with_choices(None, vec![EMOJI_ID]).await;
+    let user = setup.user.insecure_clone();
+
+    let ix = bet_on_ix(&setup, user.pubkey(), OTHER_EMOJI_ID, 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::EmojiNotAllowed);
+
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    println!("✅ bet only accepts the market's choices");
+}
+
+#[tokio::test]
+async fn test_bet_rejects_disabled_emoji() {
+    let mut setup = setup_open_market().await;
+    let admin = setup.admin.insecure_clone();
+    let user = setup.user.insecure_clone();
+
+    let ix = update_emoji_ix(&setup, admin.pubkey(), EMOJI_ID, None, false);
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    let ix = bet_ix(&setup, 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::EmojiDisabled);
+
+    // Re-enabling the emoji opens it to bets again
+    let ix = update_emoji_ix(&setup, admin.pubkey(), EMOJI_ID, None, true);
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    next_blockhash(&mut setup.context).await;
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    println!("✅ Disabled emojis take no bets");
+}
+
+#[tokio::test]
+async fn test_bet_rejects_unregistered_emoji() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+
+    let ix = bet_on_ix(&setup, user.pubkey(), UNREGISTERED_EMOJI_ID, 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, anchor_lang::error::ErrorCode::AccountNotInitialized);
+    println!("✅ Unregistered emojis take no bets");
+}
+
+#[tokio::test]
+async fn test_update_emoji_is_admin_only() {
+    let mut setup = setup_open_market().await;
+    let stranger = Keypair::new();
+    fund(&mut setup.context, &stranger.pubkey(), 1_000_000_000).await;
+
+    let ix = update_emoji_ix(&setup, stranger.pubkey(), EMOJI_ID, None, false);
+    let result = send(&mut setup.context, ix, &[&stranger]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    println!("✅ Only the admin can update emojis");
+}
+
+#[tokio::test]
+async fn test_update_emoji_changes_sequence() {
+    let mut setup = setup_open_market().await;
+    let admin = setup.admin.insecure_clone();
+
+    let ix = update_emoji_ix(&setup, admin.pubkey(), EMOJI_ID, Some("💯"), true);
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(emoji_pda(EMOJI_ID))
+        .await
+        .unwrap()
+        .unwrap();
+    let entry = EmojiEntry::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(entry.emoji_id, EMOJI_ID);
+    assert_eq!(entry.sequence, "💯");
+    assert!(entry.enabled);
+    println!("✅ update_emoji rewrites the sequence");
+}
@@ -0,0 +1,135 @@
+// Processor tests for batched bets
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, system_program};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::instructions::BetOrder;
+use emojimarket_program::state::BetAccount;
+use solana_program_test::tokio;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::signature::Signer;
+
+fn bet_many_ix(setup: &Setup, orders: Vec<BetOrder>, max_total_cost: u64) -> Instruction {
+    let mut accounts = emojimarket_program::accounts::BetMany {
+        config: setup.config,
+        market: setup.market,
+        bet: setup.bet,
+        user: setup.user.pubkey(),
+        user_token_account: None,
+        mint: None,
+        vault: None,
+        token_program: None,
+        system_program: system_program::ID,
+        event_authority: event_authority(),
+        program: emojimarket_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(
+        orders
+            .iter()
+            .map(|order| AccountMeta::new_readonly(emoji_pda(order.emoji_id), false)),
+    );
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts,
+        data: emojimarket_program::instruction::BetMany {
+            _market_id: MARKET_ID,
+            orders,
+            max_total_cost,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_bet_many_matches_sequential_bets() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let orders = vec![
+        BetOrder {
+            emoji_id: EMOJI_ID,
+            vote_qty: 3,
+        },
+        BetOrder {
+            emoji_id: EMOJI_ID,
+            vote_qty: 4,
+        },
+    ];
+    let ix = bet_many_ix(&setup, orders, u64::MAX);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let batched = market_state(&mut setup).await;
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.bet)
+        .await
+        .unwrap()
+        .unwrap();
+    let bet = BetAccount::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(batched.total_votes, 7);
+    assert_eq!(bet.emoji_ids, vec![EMOJI_ID]);
+    assert_eq!(bet.emoji_votes, vec![7]);
+    assert_eq!(bet.total_spent, batched.total_pot);
+
+    // Pricing each order after the previous one costs the same as one bet
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 7);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let single = market_state(&mut setup).await;
+    assert_eq!(batched.total_pot, single.total_pot);
+    println!("✅ A batch costs the same as its orders placed one by one");
+}
+
+#[tokio::test]
+async fn test_bet_many_applies_slippage_to_the_batch() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let orders = vec![
+        BetOrder {
+            emoji_id: EMOJI_ID,
+            vote_qty: 1,
+        };
+        2
+    ];
+    let ix = bet_many_ix(&setup, orders, 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::SlippageExceeded);
+    println!("✅ max_total_cost bounds the whole batch");
+}
+
+#[tokio::test]
+async fn test_bet_many_rejects_empty_batch() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_many_ix(&setup, vec![], u64::MAX);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::InvalidBatch);
+    println!("✅ Empty batches are rejected");
+}
+
+#[tokio::test]
+async fn test_bet_many_rejects_new_emoji_past_market_cap() {
+    let mut setup = setup_open_market_with_choices(Some(1), Vec::new()).await;
+    let user = setup.user.insecure_clone();
+
+    let orders = vec![
+        BetOrder {
+            emoji_id: EMOJI_ID,
+            vote_qty: 1,
+        },
+        BetOrder {
+            emoji_id: OTHER_EMOJI_ID,
+            vote_qty: 1,
+        },
+    ];
+    let ix = bet_many_ix(&setup, orders, u64::MAX);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::TooManyEmojis);
+    println!("✅ bet_many stops at the market's emoji cap");
+}
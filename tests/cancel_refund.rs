@@ -0,0 +1,132 @@
+// Processor tests for cancelling markets and refunding bettors
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::BetAccount;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Open market with a single 10-vote bet, cancelled by the admin
+async fn setup_cancelled_market() -> Setup {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let admin = setup.admin.insecure_clone();
+    let ix = cancel_market_ix(&setup, admin.pubkey());
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    setup
+}
+
+#[tokio::test]
+async fn test_creator_cancels_only_before_first_bet() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+    let user = setup.user.insecure_clone();
+
+    // Neither admin nor creator
+    let ix = cancel_market_ix(&setup, user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = cancel_market_ix(&setup, creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::MarketHasBets);
+    println!("✅ Creators can't cancel once bets are placed");
+}
+
+#[tokio::test]
+async fn test_creator_cancels_empty_market() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+    let ix = cancel_market_ix(&setup, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    let market = market_state(&mut setup).await;
+    assert_eq!(market.status, 2);
+    assert!(market.claim_deadline_ts > 0);
+    println!("✅ Creators can cancel a market nobody bet on");
+}
+
+#[tokio::test]
+async fn test_refund_returns_everything_without_fees() {
+    let mut setup = setup_cancelled_market().await;
+    let user = setup.user.insecure_clone();
+    let market = market_state(&mut setup).await;
+    assert_eq!(market.status, 2);
+    assert_eq!(market.platform_fee_taken, 0);
+    assert_eq!(market.creator_fee_taken, 0);
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.bet)
+        .await
+        .unwrap()
+        .unwrap();
+    let spent = BetAccount::try_deserialize(&mut account.data.as_ref())
+        .unwrap()
+        .total_spent;
+    assert_eq!(spent, market.total_pot);
+
+    let before = setup
+        .context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    let ix = refund_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let after = setup
+        .context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(after - before, spent);
+
+    // A second refund is rejected
+    next_blockhash(&mut setup.context).await;
+    let ix = refund_ix(&setup, user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::AlreadyClaimed);
+    println!("✅ Refunds return total_spent once, with no fees");
+}
+
+#[tokio::test]
+async fn test_refund_rejected_after_claim_window() {
+    let mut setup = setup_cancelled_market().await;
+    let user = setup.user.insecure_clone();
+    let deadline = market_state(&mut setup).await.claim_deadline_ts;
+    set_time(&mut setup.context, deadline).await;
+
+    let ix = refund_ix(&setup, user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::ClaimWindowClosed);
+    println!("✅ Refunds close with the claim window");
+}
+
+#[tokio::test]
+async fn test_refund_rejected_on_active_market() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let ix = refund_ix(&setup, user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::MarketNotCancelled);
+
+    // Nobody else can refund on the user's behalf either
+    let stranger = Keypair::new();
+    fund(&mut setup.context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = refund_ix(&setup, stranger.pubkey());
+    assert!(send(&mut setup.context, ix, &[&stranger]).await.is_err());
+    println!("✅ Only cancelled markets refund");
+}
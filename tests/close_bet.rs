@@ -0,0 +1,99 @@
+// Processor tests for closing settled bets
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+async fn balance(setup: &mut Setup, key: Pubkey) -> u64 {
+    setup.context.banks_client.get_balance(key).await.unwrap()
+}
+
+/// Ended market the user won with 10 votes; the returned bettor lost with
+/// 3 votes on another emoji
+async fn setup_winner_and_loser() -> (Setup, Keypair) {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let loser = add_bettor(&mut setup).await;
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = bet_on_ix(&setup, loser.pubkey(), OTHER_EMOJI_ID, 3);
+    send(&mut setup.context, ix, &[&loser]).await.unwrap();
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let creator = setup.creator.insecure_clone();
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    (setup, loser)
+}
+
+#[tokio::test]
+async fn test_loser_closes_bet_and_gets_rent_back() {
+    let (mut setup, loser) = setup_winner_and_loser().await;
+    let bet = bet_pda(&setup.market, &loser.pubkey());
+    assert_eq!(market_state(&mut setup).await.open_bets, 2);
+
+    let rent = balance(&mut setup, bet).await;
+    let before = balance(&mut setup, loser.pubkey()).await;
+    let ix = close_bet_ix(&setup, loser.pubkey(), loser.pubkey());
+    send(&mut setup.context, ix, &[&loser]).await.unwrap();
+
+    assert_eq!(balance(&mut setup, loser.pubkey()).await - before, rent);
+    assert_eq!(market_state(&mut setup).await.open_bets, 1);
+    println!("✅ Losing bets close right after the market ends");
+}
+
+#[tokio::test]
+async fn test_winner_closes_only_after_claiming() {
+    let (mut setup, _) = setup_winner_and_loser().await;
+    let user = setup.user.insecure_clone();
+
+    let ix = close_bet_ix(&setup, user.pubkey(), user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::UnclaimedFunds);
+
+    // Nobody else can close it during the claim window
+    let stranger = Keypair::new();
+    fund(&mut setup.context, &stranger.pubkey(), 1_000_000_000).await;
+    let ix = close_bet_ix(&setup, user.pubkey(), stranger.pubkey());
+    let result = send(&mut setup.context, ix, &[&stranger]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+
+    let ix = claim_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = close_bet_ix(&setup, user.pubkey(), user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    assert_eq!(market_state(&mut setup).await.open_bets, 1);
+
+    // The closed bet can't be claimed again
+    next_blockhash(&mut setup.context).await;
+    let ix = claim_ix(&setup, user.pubkey());
+    assert!(send(&mut setup.context, ix, &[&user]).await.is_err());
+    println!("✅ Winners close their bet once the payout is claimed");
+}
+
+#[tokio::test]
+async fn test_anyone_closes_bets_after_deadline() {
+    let (mut setup, _) = setup_winner_and_loser().await;
+    let user = setup.user.pubkey();
+    let bet = setup.bet;
+    let deadline = market_state(&mut setup).await.claim_deadline_ts;
+    set_time(&mut setup.context, deadline).await;
+
+    // An unclaimed winner's bet, closed by a stranger, still refunds the owner's rent
+    let stranger = Keypair::new();
+    fund(&mut setup.context, &stranger.pubkey(), 1_000_000_000).await;
+    let rent = balance(&mut setup, bet).await;
+    let before = balance(&mut setup, user).await;
+    let ix = close_bet_ix(&setup, user, stranger.pubkey());
+    send(&mut setup.context, ix, &[&stranger]).await.unwrap();
+
+    assert_eq!(balance(&mut setup, user).await - before, rent);
+    assert_eq!(balance(&mut setup, stranger.pubkey()).await, 1_000_000_000);
+    assert_eq!(market_state(&mut setup).await.open_bets, 1);
+    println!("✅ Expired bets can be closed by anyone, rent goes to the owner");
+}
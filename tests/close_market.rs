@@ -0,0 +1,123 @@
+// Processor tests for the claim window and closing markets
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::Treasury;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+/// Ended market whose winner never claimed, clock past the claim deadline
+async fn setup_expired_market() -> Setup {
+    let mut setup = setup_ended_market().await;
+    let creator = setup.creator.insecure_clone();
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    let deadline = market_state(&mut setup).await.claim_deadline_ts;
+    set_time(&mut setup.context, deadline).await;
+    setup
+}
+
+#[tokio::test]
+async fn test_close_market_sweeps_unclaimed_funds_into_treasury() {
+    let mut setup = setup_expired_market().await;
+    let creator = setup.creator.insecure_clone();
+    let user = setup.user.pubkey();
+
+    let ix = close_bet_ix(&setup, user, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    let market = market_state(&mut setup).await;
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+    let unclaimed = account.lamports - rent.minimum_balance(account.data.len());
+    let expected = market.total_pot - market.platform_fee_taken - market.creator_fee_taken;
+    assert_eq!(unclaimed, expected);
+
+    let treasury_before = setup
+        .context
+        .banks_client
+        .get_balance(setup.treasury)
+        .await
+        .unwrap();
+    let ix = close_market_ix(&setup, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    assert!(setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .is_none());
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.treasury)
+        .await
+        .unwrap()
+        .unwrap();
+    let treasury = Treasury::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(account.lamports - treasury_before, unclaimed);
+    assert_eq!(
+        treasury.total_collected,
+        market.platform_fee_taken + unclaimed
+    );
+    println!("✅ Unclaimed winnings are swept into the treasury");
+}
+
+#[tokio::test]
+async fn test_claim_rejected_after_deadline() {
+    let mut setup = setup_expired_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = claim_ix(&setup, user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::ClaimWindowClosed);
+    println!("✅ Claims close with the claim window");
+}
+
+#[tokio::test]
+async fn test_close_market_waits_for_claim_window() {
+    let mut setup = setup_ended_market().await;
+    let creator = setup.creator.insecure_clone();
+    let user = setup.user.insecure_clone();
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    // Even with every bet settled and closed, winners keep their window
+    let ix = claim_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = close_bet_ix(&setup, user.pubkey(), user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = close_market_ix(&setup, creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::ClaimWindowOpen);
+    println!("✅ Markets can't be closed during the claim window");
+}
+
+#[tokio::test]
+async fn test_close_market_requires_closed_bets() {
+    let mut setup = setup_expired_market().await;
+    let creator = setup.creator.insecure_clone();
+    let ix = close_market_ix(&setup, creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::OpenBetsRemaining);
+
+    // Only the admin or the creator can close it
+    let user = setup.user.insecure_clone();
+    let ix = close_bet_ix(&setup, user.pubkey(), user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = close_market_ix(&setup, user.pubkey());
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+    println!("✅ Markets close once every bet is closed");
+}
@@ -0,0 +1,765 @@
+// Shared solana-program-test harness for the processor tests
+//
+// These run the program in-process, so account constraints are exercised
+// exactly as on-chain.
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    clock::Clock, instruction::Instruction, program_pack::Pack, system_program,
+};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{
+    get_associated_token_address_with_program_id, spl_associated_token_account,
+};
+use anchor_spl::token::spl_token;
+use anchor_spl::token_2022::spl_token_2022;
+use emojimarket_program::state::{BetAccount, Market};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_token_2022::extension::{transfer_fee, ExtensionType};
+
+pub const MARKET_ID: u64 = 1;
+pub const EMOJI_ID: u32 = 128_293;
+pub const OTHER_EMOJI_ID: u32 = 128_169;
+pub const DURATION_SECS: i64 = 3_600;
+pub const TOKEN_BASE_PRICE: u64 = 10_000;
+
+// Anchor's entrypoint ties the accounts slice to its AccountInfo lifetime,
+// which the program-test processor signature can't express
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> anchor_lang::solana_program::entrypoint::ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    emojimarket_program::entry(program_id, accounts, data)
+}
+
+pub struct Setup {
+    pub context: ProgramTestContext,
+    pub admin: Keypair,
+    pub creator: Keypair,
+    pub user: Keypair,
+    pub config: Pubkey,
+    pub treasury: Pubkey,
+    pub market: Pubkey,
+    pub bet: Pubkey,
+    pub end_ts: i64,
+    // Set for token markets
+    pub mint: Option<Pubkey>,
+    pub token_program: Pubkey,
+}
+
+pub fn event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"__event_authority"], &emojimarket_program::ID).0
+}
+
+pub async fn send(
+    context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> std::result::Result<(), BanksClientError> {
+    let payer = context.payer.insecure_clone();
+    let mut all_signers = vec![&payer];
+    all_signers.extend_from_slice(signers);
+    let blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &all_signers,
+        blockhash,
+    );
+    context.banks_client.process_transaction(tx).await
+}
+
+/// Wait for a blockhash newer than the one `send` last used, so resending the
+/// same instruction isn't deduplicated as an already processed transaction
+pub async fn next_blockhash(context: &mut ProgramTestContext) {
+    context.last_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+    context.get_new_latest_blockhash().await.unwrap();
+}
+
+pub async fn fund(context: &mut ProgramTestContext, to: &Pubkey, lamports: u64) {
+    let payer = context.payer.pubkey();
+    send(
+        context,
+        system_instruction::transfer(&payer, to, lamports),
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+pub async fn set_time(context: &mut ProgramTestContext, unix_timestamp: i64) {
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = unix_timestamp;
+    context.set_sysvar(&clock);
+}
+
+/// Config, two registered emojis and one open SOL market without bets
+pub async fn setup_open_market() -> Setup {
+    setup_market(None, None, Vec::new()).await
+}
+
+/// Same as `setup_open_market`, denominated in a fresh SPL mint the user holds
+pub async fn setup_open_token_market() -> Setup {
+    setup_market(Some((spl_token::ID, 0)), None, Vec::new()).await
+}
+
+/// Same as `setup_open_token_market` with a Token-2022 mint charging
+/// `transfer_fee_bps` on every transfer
+pub async fn setup_open_token_2022_market(transfer_fee_bps: u16) -> Setup {
+    setup_market(
+        Some((spl_token_2022::ID, transfer_fee_bps)),
+        None,
+        Vec::new(),
+    )
+    .await
+}
+
+/// Same as `setup_open_market` with an emoji cap and a list of allowed choices
+pub async fn setup_open_market_with_choices(
+    max_emojis: Option<u8>,
+    allowed_emoji_ids: Vec<u32>,
+) -> Setup {
+    setup_market(None, max_emojis, allowed_emoji_ids).await
+}
+
+async fn setup_market(
+    token: Option<(Pubkey, u16)>,
+    max_emojis: Option<u8>,
+    allowed_emoji_ids: Vec<u32>,
+) -> Setup {
+    let program_test = ProgramTest::new(
+        "emojimarket_program",
+        emojimarket_program::ID,
+        processor!(process_instruction),
+    );
+    let mut context = program_test.start_with_context().await;
+
+    let admin = Keypair::new();
+    let creator = Keypair::new();
+    let user = Keypair::new();
+    for key in [admin.pubkey(), creator.pubkey(), user.pubkey()] {
+        fund(&mut context, &key, 10_000_000_000).await;
+    }
+
+    let program_id = emojimarket_program::ID;
+    let config = Pubkey::find_program_address(&[b"config"], &program_id).0;
+    let treasury = Pubkey::find_program_address(&[b"treasury"], &program_id).0;
+    let market = market_pda(&creator.pubkey(), MARKET_ID);
+    let bet = bet_pda(&market, &user.pubkey());
+
+    let payer = context.payer.pubkey();
+    let ix = Instruction {
+        program_id,
+        accounts: emojimarket_program::accounts::InitializeConfig {
+            config,
+            treasury,
+            payer,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: program_id,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::InitializeConfig {
+            admin_address: admin.pubkey(),
+            platform_fee_bps: 500,
+            creator_fee_bps: 300,
+            base_price_lamports: 1_000_000,
+            malus_k_millis: 1_000,
+            quad_a_micros: 10_000,
+            quad_b_micros: 1_000,
+            min_duration_secs: 60,
+            max_duration_secs: 86_400,
+            claim_window_secs: 604_800,
+            end_grace_secs: 86_400,
+            crank_fee_bps: 1_000,
+        }
+        .data(),
+    };
+    send(&mut context, ix, &[]).await.unwrap();
+
+    for (emoji_id, sequence) in [(EMOJI_ID, "🔥"), (OTHER_EMOJI_ID, "💩")] {
+        let ix = register_emoji_ix(admin.pubkey(), emoji_id, sequence);
+        send(&mut context, ix, &[&admin]).await.unwrap();
+    }
+
+    let clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    let end_ts = clock.unix_timestamp + DURATION_SECS;
+
+    let mut setup = Setup {
+        context,
+        admin,
+        creator,
+        user,
+        config,
+        treasury,
+        market,
+        bet,
+        end_ts,
+        mint: None,
+        token_program: spl_token::ID,
+    };
+    if let Some((token_program, transfer_fee_bps)) = token {
+        setup_mint(&mut setup, token_program, transfer_fee_bps).await;
+    }
+    let ix = create_market_with_choices_ix(&setup, MARKET_ID, max_emojis, allowed_emoji_ids);
+    let creator = setup.creator.insecure_clone();
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    setup
+}
+
+/// Create a mint, accept it for markets and fund the user's token account
+async fn setup_mint(setup: &mut Setup, token_program: Pubkey, transfer_fee_bps: u16) {
+    let mint = Keypair::new();
+    let admin = setup.admin.insecure_clone();
+    let payer = setup.context.payer.pubkey();
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+
+    let mut instructions = Vec::new();
+    let space = if token_program == spl_token_2022::ID {
+        ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+        ])
+        .unwrap()
+    } else {
+        spl_token::state::Mint::LEN
+    };
+    instructions.push(system_instruction::create_account(
+        &payer,
+        &mint.pubkey(),
+        rent.minimum_balance(space),
+        space as u64,
+        &token_program,
+    ));
+    if token_program == spl_token_2022::ID {
+        instructions.push(
+            transfer_fee::instruction::initialize_transfer_fee_config(
+                &token_program,
+                &mint.pubkey(),
+                None,
+                None,
+                transfer_fee_bps,
+                u64::MAX,
+            )
+            .unwrap(),
+        );
+    }
+    instructions.push(
+        spl_token_2022::instruction::initialize_mint(
+            &token_program,
+            &mint.pubkey(),
+            &admin.pubkey(),
+            None,
+            6,
+        )
+        .unwrap(),
+    );
+    let blockhash = setup
+        .context
+        .banks_client
+        .get_latest_blockhash()
+        .await
+        .unwrap();
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer),
+        &[&setup.context.payer, &mint],
+        blockhash,
+    );
+    setup
+        .context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .unwrap();
+    setup.mint = Some(mint.pubkey());
+    setup.token_program = token_program;
+
+    // The treasury and the creator need token accounts to receive fees
+    let user = setup.user.pubkey();
+    for owner in [user, setup.creator.pubkey(), setup.treasury] {
+        create_token_account(setup, &owner).await;
+    }
+    let ix = spl_token_2022::instruction::mint_to(
+        &token_program,
+        &mint.pubkey(),
+        &token_account(setup, &user),
+        &admin.pubkey(),
+        &[],
+        1_000_000_000_000,
+    )
+    .unwrap();
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+
+    let ix = Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::SetAcceptedMint {
+            config: setup.config,
+            mint: mint.pubkey(),
+            accepted_mint: accepted_mint_pda(&mint.pubkey()),
+            admin: admin.pubkey(),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::SetAcceptedMint {
+            base_price: TOKEN_BASE_PRICE,
+            enabled: true,
+        }
+        .data(),
+    };
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+}
+
+/// Associated token account of `owner` for the setup's mint
+pub fn token_account(setup: &Setup, owner: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, &setup.mint.unwrap(), &setup.token_program)
+}
+
+pub async fn create_token_account(setup: &mut Setup, owner: &Pubkey) {
+    let payer = setup.context.payer.pubkey();
+    let ix = spl_associated_token_account::instruction::create_associated_token_account(
+        &payer,
+        owner,
+        &setup.mint.unwrap(),
+        &setup.token_program,
+    );
+    send(&mut setup.context, ix, &[]).await.unwrap();
+}
+
+pub async fn token_balance(setup: &mut Setup, owner: &Pubkey) -> u64 {
+    let account = setup
+        .context
+        .banks_client
+        .get_account(token_account(setup, owner))
+        .await
+        .unwrap()
+        .unwrap();
+    // Token-2022 extensions are appended after the base account layout
+    spl_token::state::Account::unpack(&account.data[..spl_token::state::Account::LEN])
+        .unwrap()
+        .amount
+}
+
+/// Same market with a single 10-vote bet, clock at end_ts
+pub async fn setup_ended_market() -> Setup {
+    let mut setup = setup_open_market().await;
+    place_bet_and_end(&mut setup).await;
+    setup
+}
+
+/// Token market with a single 10-vote bet, clock at end_ts
+pub async fn setup_ended_token_market() -> Setup {
+    let mut setup = setup_open_token_market().await;
+    place_bet_and_end(&mut setup).await;
+    setup
+}
+
+/// Fund another wallet able to bet on the setup's market (and hold its mint)
+pub async fn add_bettor(setup: &mut Setup) -> Keypair {
+    let bettor = Keypair::new();
+    fund(&mut setup.context, &bettor.pubkey(), 10_000_000_000).await;
+    if let Some(mint) = setup.mint {
+        create_token_account(setup, &bettor.pubkey()).await;
+        let admin = setup.admin.insecure_clone();
+        let ix = spl_token_2022::instruction::mint_to(
+            &setup.token_program,
+            &mint,
+            &token_account(setup, &bettor.pubkey()),
+            &admin.pubkey(),
+            &[],
+            1_000_000_000_000,
+        )
+        .unwrap();
+        send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    }
+    bettor
+}
+
+async fn place_bet_and_end(setup: &mut Setup) {
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+}
+
+pub async fn market_state(setup: &mut Setup) -> Market {
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    Market::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+pub async fn bet_state(setup: &mut Setup, bet: Pubkey) -> BetAccount {
+    let account = setup
+        .context
+        .banks_client
+        .get_account(bet)
+        .await
+        .unwrap()
+        .unwrap();
+    BetAccount::try_deserialize(&mut account.data.as_ref()).unwrap()
+}
+
+pub fn accepted_mint_pda(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"accepted_mint", mint.as_ref()], &emojimarket_program::ID).0
+}
+
+pub fn emoji_pda(emoji_id: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"emoji", &emoji_id.to_le_bytes()],
+        &emojimarket_program::ID,
+    )
+    .0
+}
+
+pub fn bet_pda(market: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"bet", market.as_ref(), user.as_ref()],
+        &emojimarket_program::ID,
+    )
+    .0
+}
+
+pub fn market_pda(creator: &Pubkey, market_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"market", creator.as_ref(), &market_id.to_le_bytes()],
+        &emojimarket_program::ID,
+    )
+    .0
+}
+
+pub fn register_emoji_ix(admin: Pubkey, emoji_id: u32, sequence: &str) -> Instruction {
+    let config = Pubkey::find_program_address(&[b"config"], &emojimarket_program::ID).0;
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::RegisterEmoji {
+            config,
+            emoji: emoji_pda(emoji_id),
+            admin,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::RegisterEmoji {
+            emoji_id,
+            sequence: sequence.to_string(),
+        }
+        .data(),
+    }
+}
+
+pub fn create_market_ix(setup: &Setup, market_id: u64) -> Instruction {
+    create_market_with_choices_ix(setup, market_id, None, Vec::new())
+}
+
+/// `create_market_ix` with an emoji cap and a list of allowed choices, each
+/// passed with its registry entry
+pub fn create_market_with_choices_ix(
+    setup: &Setup,
+    market_id: u64,
+    max_emojis: Option<u8>,
+    allowed_emoji_ids: Vec<u32>,
+) -> Instruction {
+    let creator = setup.creator.pubkey();
+    let mut accounts = emojimarket_program::accounts::CreateMarket {
+        config: setup.config,
+        market: market_pda(&creator, market_id),
+        creator,
+        mint: setup.mint,
+        accepted_mint: setup.mint.as_ref().map(accepted_mint_pda),
+        vault: setup
+            .mint
+            .map(|_| token_account(setup, &market_pda(&creator, market_id))),
+        token_program: setup.mint.map(|_| setup.token_program),
+        associated_token_program: setup.mint.map(|_| spl_associated_token_account::ID),
+        system_program: system_program::ID,
+        event_authority: event_authority(),
+        program: emojimarket_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(
+        allowed_emoji_ids
+            .iter()
+            .map(|&id| AccountMeta::new_readonly(emoji_pda(id), false)),
+    );
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts,
+        data: emojimarket_program::instruction::CreateMarket {
+            market_id,
+            title: "🔥 or not".to_string(),
+            image_url: None,
+            end_ts: setup.end_ts,
+            tie_policy: 0,
+            payout_bps: Vec::new(),
+            max_emojis,
+            allowed_emoji_ids,
+        }
+        .data(),
+    }
+}
+
+pub fn bet_ix(setup: &Setup, vote_qty: u64) -> Instruction {
+    bet_ix_for(setup, setup.user.pubkey(), vote_qty)
+}
+
+pub fn bet_ix_for(setup: &Setup, user: Pubkey, vote_qty: u64) -> Instruction {
+    bet_on_ix(setup, user, EMOJI_ID, vote_qty)
+}
+
+pub fn bet_on_ix(setup: &Setup, user: Pubkey, emoji_id: u32, vote_qty: u64) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::PlaceBet {
+            config: setup.config,
+            market: setup.market,
+            bet: bet_pda(&setup.market, &user),
+            emoji: emoji_pda(emoji_id),
+            user,
+            user_token_account: setup.mint.map(|_| token_account(setup, &user)),
+            mint: setup.mint,
+            vault: setup.mint.map(|_| token_account(setup, &setup.market)),
+            token_program: setup.mint.map(|_| setup.token_program),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::Bet {
+            _market_id: MARKET_ID,
+            emoji_id,
+            vote_qty,
+            max_total_cost: u64::MAX,
+        }
+        .data(),
+    }
+}
+
+pub fn end_market_ix(setup: &Setup, caller: Pubkey, market_creator: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::EndMarket {
+            config: setup.config,
+            market: setup.market,
+            caller,
+            treasury: setup.treasury,
+            market_creator,
+            mint: setup.mint,
+            vault: setup.mint.map(|_| token_account(setup, &setup.market)),
+            treasury_token_account: setup.mint.map(|_| token_account(setup, &setup.treasury)),
+            creator_token_account: setup
+                .mint
+                .map(|_| token_account(setup, &setup.creator.pubkey())),
+            caller_token_account: setup.mint.map(|_| token_account(setup, &caller)),
+            token_program: setup.mint.map(|_| setup.token_program),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::EndMarket {
+            market_id: MARKET_ID,
+        }
+        .data(),
+    }
+}
+
+pub fn sell_votes_ix(setup: &Setup, vote_qty: u64, min_refund: u64) -> Instruction {
+    let user = setup.user.pubkey();
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::SellVotes {
+            config: setup.config,
+            market: setup.market,
+            bet: setup.bet,
+            user,
+            user_token_account: setup.mint.map(|_| token_account(setup, &user)),
+            mint: setup.mint,
+            vault: setup.mint.map(|_| token_account(setup, &setup.market)),
+            token_program: setup.mint.map(|_| setup.token_program),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::SellVotes {
+            market_id: MARKET_ID,
+            emoji_id: EMOJI_ID,
+            vote_qty,
+            min_refund,
+        }
+        .data(),
+    }
+}
+
+pub fn cancel_market_ix(setup: &Setup, caller: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::CancelMarket {
+            config: setup.config,
+            market: setup.market,
+            caller,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::CancelMarket {
+            _market_id: MARKET_ID,
+        }
+        .data(),
+    }
+}
+
+pub fn refund_ix(setup: &Setup, user: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::Refund {
+            config: setup.config,
+            market: setup.market,
+            bet: bet_pda(&setup.market, &user),
+            user,
+            user_token_account: setup.mint.map(|_| token_account(setup, &user)),
+            mint: setup.mint,
+            vault: setup.mint.map(|_| token_account(setup, &setup.market)),
+            token_program: setup.mint.map(|_| setup.token_program),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::Refund {
+            market_id: MARKET_ID,
+        }
+        .data(),
+    }
+}
+
+pub fn claim_ix(setup: &Setup, user: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::Claim {
+            config: setup.config,
+            market: setup.market,
+            bet: bet_pda(&setup.market, &user),
+            user,
+            user_token_account: setup.mint.map(|_| token_account(setup, &user)),
+            mint: setup.mint,
+            vault: setup.mint.map(|_| token_account(setup, &setup.market)),
+            token_program: setup.mint.map(|_| setup.token_program),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::Claim {
+            market_id: MARKET_ID,
+        }
+        .data(),
+    }
+}
+
+pub fn close_bet_ix(setup: &Setup, user: Pubkey, closer: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::CloseBet {
+            config: setup.config,
+            market: setup.market,
+            bet: bet_pda(&setup.market, &user),
+            user,
+            closer,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::CloseBet {
+            _market_id: MARKET_ID,
+        }
+        .data(),
+    }
+}
+
+pub fn close_market_ix(setup: &Setup, caller: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::CloseMarket {
+            config: setup.config,
+            market: setup.market,
+            caller,
+            market_creator: setup.creator.pubkey(),
+            treasury: setup.treasury,
+            mint: setup.mint,
+            vault: setup.mint.map(|_| token_account(setup, &setup.market)),
+            treasury_token_account: setup.mint.map(|_| token_account(setup, &setup.treasury)),
+            token_program: setup.mint.map(|_| setup.token_program),
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::CloseMarket {
+            market_id: MARKET_ID,
+        }
+        .data(),
+    }
+}
+
+pub fn set_pause_ix(
+    setup: &Setup,
+    admin: Pubkey,
+    markets_paused: bool,
+    bets_paused: bool,
+    withdrawals_paused: bool,
+) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::SetPause {
+            config: setup.config,
+            admin,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::SetPause {
+            markets_paused,
+            bets_paused,
+            withdrawals_paused,
+        }
+        .data(),
+    }
+}
+
+pub async fn set_pause(setup: &mut Setup, markets: bool, bets: bool, withdrawals: bool) {
+    let admin = setup.admin.insecure_clone();
+    let ix = set_pause_ix(setup, admin.pubkey(), markets, bets, withdrawals);
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+}
+
+/// Takes the program's `ErrorCode` or one of Anchor's framework errors
+pub fn assert_custom_error<E: Into<u32> + std::fmt::Debug>(
+    result: std::result::Result<(), BanksClientError>,
+    error: E,
+) {
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, error.into()),
+        other => panic!("expected {:?}, got {:?}", error, other),
+    }
+}
@@ -0,0 +1,30 @@
+// Processor tests for markets created with a list of choices
+
+mod common;
+
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use solana_program_test::tokio;
+
+#[tokio::test]
+async fn test_create_market_rejects_duplicate_choices() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+
+    let ix = create_market_with_choices_ix(&setup, MARKET_ID + 1, None, vec![EMOJI_ID, EMOJI_ID]);
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::DuplicateEmoji);
+    println!("✅ Choices must be unique");
+}
+
+#[tokio::test]
+async fn test_create_market_prefills_choices_with_zero_votes() {
+    let mut setup = setup_open_market_with_choices(None, vec![OTHER_EMOJI_ID, EMOJI_ID]).await;
+
+    let market = market_state(&mut setup).await;
+    assert!(market.restricted_emojis);
+    assert_eq!(market.emoji_ids, vec![OTHER_EMOJI_ID, EMOJI_ID]);
+    assert_eq!(market.emoji_votes, vec![0, 0]);
+    assert_eq!(market.total_votes, 0);
+    println!("✅ Choices start at zero votes in the given order");
+}
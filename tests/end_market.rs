@@ -0,0 +1,308 @@
+// Processor tests for end_market fee routing
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::{Market, Treasury};
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Same params as the setup's config except the grace period and crank fee
+fn update_grace_and_crank_ix(
+    setup: &Setup,
+    end_grace_secs: u32,
+    crank_fee_bps: u16,
+) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::UpdateConfig {
+            config: setup.config,
+            admin: setup.admin.pubkey(),
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::UpdateConfig {
+            platform_fee_bps: 500,
+            creator_fee_bps: 300,
+            base_price_lamports: 1_000_000,
+            malus_k_millis: 1000,
+            quad_a_micros: 10_000,
+            quad_b_micros: 1_000,
+            min_duration_secs: 60,
+            max_duration_secs: 86_400,
+            claim_window_secs: 604_800,
+            end_grace_secs,
+            crank_fee_bps,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_end_market_rejects_creator_fee_redirect() {
+    let mut setup = setup_ended_market().await;
+    let admin = setup.admin.insecure_clone();
+
+    // Admin ends the market but routes the creator fee elsewhere
+    let ix = end_market_ix(&setup, admin.pubkey(), admin.pubkey());
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::InvalidFeeRecipient);
+    println!("✅ Creator fee can't be redirected by the admin");
+}
+
+#[tokio::test]
+async fn test_end_market_pays_fees_into_treasury() {
+    let mut setup = setup_ended_market().await;
+    let creator = setup.creator.insecure_clone();
+    let creator_key = creator.pubkey();
+
+    let treasury_before = setup
+        .context
+        .banks_client
+        .get_balance(setup.treasury)
+        .await
+        .unwrap();
+    let ix = end_market_ix(&setup, creator_key, creator_key);
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.treasury)
+        .await
+        .unwrap()
+        .unwrap();
+    let treasury = Treasury::try_deserialize(&mut account.data.as_ref()).unwrap();
+
+    assert!(market.platform_fee_taken > 0);
+    assert_eq!(
+        account.lamports - treasury_before,
+        market.platform_fee_taken
+    );
+    assert_eq!(treasury.total_collected, market.platform_fee_taken);
+    println!("✅ Platform fee accrues in the treasury");
+}
+
+#[tokio::test]
+async fn test_end_market_rejects_crank_during_grace_period() {
+    let mut setup = setup_ended_market().await;
+    let keeper = Keypair::new();
+    fund(&mut setup.context, &keeper.pubkey(), 1_000_000_000).await;
+
+    let creator = setup.creator.pubkey();
+    let ix = end_market_ix(&setup, keeper.pubkey(), creator);
+    let result = send(&mut setup.context, ix, &[&keeper]).await;
+    assert_custom_error(result, ErrorCode::GracePeriodActive);
+    println!("✅ Keepers wait for the grace period");
+}
+
+#[tokio::test]
+async fn test_end_market_pays_crank_fee_after_grace_period() {
+    let mut setup = setup_ended_market().await;
+    let keeper = Keypair::new();
+    fund(&mut setup.context, &keeper.pubkey(), 1_000_000_000).await;
+
+    let clock: Clock = setup.context.banks_client.get_sysvar().await.unwrap();
+    set_time(&mut setup.context, clock.unix_timestamp + 86_400).await;
+
+    let creator = setup.creator.pubkey();
+    let ix = end_market_ix(&setup, keeper.pubkey(), creator);
+    send(&mut setup.context, ix, &[&keeper]).await.unwrap();
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.treasury)
+        .await
+        .unwrap()
+        .unwrap();
+    let treasury = Treasury::try_deserialize(&mut account.data.as_ref()).unwrap();
+    let keeper_balance = setup
+        .context
+        .banks_client
+        .get_balance(keeper.pubkey())
+        .await
+        .unwrap();
+
+    // 10% of the platform fee goes to the keeper, the rest to the treasury
+    let crank_fee = market.platform_fee_taken / 10;
+    assert!(crank_fee > 0);
+    assert_eq!(keeper_balance, 1_000_000_000 + crank_fee);
+    assert_eq!(
+        treasury.total_collected,
+        market.platform_fee_taken - crank_fee
+    );
+    println!("✅ Keepers earn the crank fee from the platform's share");
+}
+
+#[tokio::test]
+async fn test_end_market_voids_market_without_votes() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(market.status, 2);
+    assert!(market.winners.is_empty());
+    assert_eq!(market.platform_fee_taken, 0);
+    assert_eq!(market.creator_fee_taken, 0);
+
+    // Nothing to claim, so it can be closed right away
+    let ix = close_market_ix(&setup, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    println!("✅ A market nobody bet on is voided and closable");
+}
+
+#[tokio::test]
+async fn test_end_market_voided_bets_refund_and_close() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let creator = setup.creator.insecure_clone();
+
+    // The only bettor sells every vote back, leaving the spread in the pot
+    let ix = bet_ix(&setup, 5);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = sell_votes_ix(&setup, 5, 0);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(market.status, 2);
+    assert_eq!(market.platform_fee_taken, 0);
+    assert!(market.total_pot > 0);
+
+    let ix = refund_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = close_bet_ix(&setup, user.pubkey(), user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    set_time(&mut setup.context, market.claim_deadline_ts).await;
+    let ix = close_market_ix(&setup, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    println!("✅ Voided markets refund their bettors and close");
+}
+
+#[tokio::test]
+async fn test_single_emoji_winner_claims_whole_pool() {
+    let mut setup = setup_ended_market().await;
+    let user = setup.user.insecure_clone();
+    let creator = setup.creator.insecure_clone();
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    let pool = market.total_pot - market.platform_fee_taken - market.creator_fee_taken;
+    assert_eq!(market.winners, vec![EMOJI_ID]);
+    assert_eq!(market.winner_payouts, vec![pool]);
+
+    let before = setup
+        .context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    let ix = claim_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let after = setup
+        .context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(after - before, pool);
+    println!("✅ The only bettor takes the whole payout pool");
+}
+
+#[tokio::test]
+async fn test_end_market_uses_snapshotted_grace_and_crank_fee() {
+    let mut setup = setup_ended_market().await;
+    let admin = setup.admin.insecure_clone();
+    let keeper = Keypair::new();
+    fund(&mut setup.context, &keeper.pubkey(), 1_000_000_000).await;
+
+    // Raising the crank fee and dropping the grace period only affects new markets
+    let ix = update_grace_and_crank_ix(&setup, 0, 5_000);
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+
+    let creator = setup.creator.pubkey();
+    let ix = end_market_ix(&setup, keeper.pubkey(), creator);
+    let result = send(&mut setup.context, ix, &[&keeper]).await;
+    assert_custom_error(result, ErrorCode::GracePeriodActive);
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts + 86_400).await;
+    next_blockhash(&mut setup.context).await;
+    let ix = end_market_ix(&setup, keeper.pubkey(), creator);
+    send(&mut setup.context, ix, &[&keeper]).await.unwrap();
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(market.end_grace_secs, 86_400);
+    assert_eq!(market.crank_fee_bps, 1_000);
+    let keeper_balance = setup
+        .context
+        .banks_client
+        .get_balance(keeper.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(
+        keeper_balance,
+        1_000_000_000 + market.platform_fee_taken / 10
+    );
+    println!("✅ Grace period and crank fee are fixed at market creation");
+}
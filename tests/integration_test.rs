@@ -5,12 +5,14 @@
 // These tests verify basic program structure.
 
 use anchor_lang::prelude::*;
+use emojimarket_program::instructions::{require_registered, validate_params, validate_sequence};
+use emojimarket_program::math::*;
 use emojimarket_program::state::*;
 
 #[test]
 fn test_config_structure() {
     // Test that Config structure is properly sized
-    let expected_min_size = 8 + 32 + 2 + 2 + 8 + 4 + 8 + 8 + 4 + 4 + 1;
+    let expected_min_size = 8 + 32 + 2 + 2 + 8 + 4 + 8 + 8 + 4 + 4 + 4 + 4 + 2 + 3 + 33 + 1;
     assert_eq!(Config::LEN, expected_min_size);
     println!("✅ Config structure size validated: {} bytes", Config::LEN);
 }
@@ -32,14 +34,35 @@ fn test_bet_structure() {
     println!("✅ BetAccount structure size validated: {} bytes", space);
 }
 
+#[test]
+fn test_emoji_cap_reserved_space() {
+    // Market and BetAccount lists are sized for MAX_EMOJIS entries
+    assert_eq!(MAX_EMOJIS, 50);
+    assert_eq!(
+        BetAccount::BASE_LEN,
+        8 + 32 + 32 + (4 + 4 * MAX_EMOJIS) + (4 + 8 * MAX_EMOJIS) + 8 + 1 + 1
+    );
+    assert_eq!(Market::space(100, 200), Market::BASE_LEN);
+    println!("✅ Emoji cap matches reserved account space");
+}
+
 #[test]
 fn test_market_status_enum() {
     // Test MarketStatus enum values
     assert_eq!(MarketStatus::Active as u8, 0);
     assert_eq!(MarketStatus::Ended as u8, 1);
+    assert_eq!(MarketStatus::Cancelled as u8, 2);
     println!("✅ MarketStatus enum values are correct");
 }
 
+#[test]
+fn test_tie_policy_enum() {
+    // Test TiePolicy enum values
+    assert_eq!(TiePolicy::LowestId as u8, 0);
+    assert_eq!(TiePolicy::Split as u8, 1);
+    println!("✅ TiePolicy enum values are correct");
+}
+
 #[test]
 fn test_pda_seeds() {
     // Test PDA derivation seeds
@@ -68,16 +91,280 @@ fn test_pda_seeds() {
     );
     assert_ne!(bet_pda, Pubkey::default());
     println!("✅ Bet PDA derivation works");
+
+    // Emoji registry PDA
+    let emoji_id: u32 = 128_293;
+    let (emoji_pda, _bump) =
+        Pubkey::find_program_address(&[b"emoji", &emoji_id.to_le_bytes()], &program_id);
+    assert_ne!(emoji_pda, Pubkey::default());
+    println!("✅ Emoji registry PDA derivation works");
 }
 
 #[test]
 fn test_program_constants() {
     // Verify important constants
-    assert!(Config::LEN > 0, "Config length should be positive");
-    assert!(BetAccount::BASE_LEN > 0, "BetAccount length should be positive");
+    const { assert!(Config::LEN > 0, "Config length should be positive") };
+    const { assert!(BetAccount::BASE_LEN > 0, "BetAccount length should be positive") };
     println!("✅ Program constants validated");
 }
 
+#[test]
+fn test_config_param_validation() {
+    // Shared by initialize_config and update_config
+    assert!(validate_params(250, 250, 1_000_000, 60, 86_400, 604_800, 1_000).is_ok());
+    assert!(validate_params(10_000, 0, 1_000_000, 60, 86_400, 604_800, 1_000).is_ok());
+    assert!(validate_params(9_000, 1_001, 1_000_000, 60, 86_400, 604_800, 1_000).is_err());
+    assert!(validate_params(250, 250, 1_000_000, 86_400, 86_400, 604_800, 1_000).is_err());
+    assert!(validate_params(250, 250, 0, 60, 86_400, 604_800, 1_000).is_err());
+    assert!(validate_params(250, 250, 1_000_000, 60, 86_400, 0, 1_000).is_err());
+    assert!(validate_params(250, 250, 1_000_000, 60, 86_400, 604_800, 10_000).is_ok());
+    assert!(validate_params(250, 250, 1_000_000, 60, 86_400, 604_800, 10_001).is_err());
+    println!("✅ Config parameter bounds are enforced");
+}
+
+#[test]
+fn test_winner_zero_bets() {
+    // No bets: no winner, end_market voids the market for refunds
+    let (winners, payouts) = calculate_tier_payouts(&[], &[], &[10_000], false, 0).unwrap();
+    assert!(winners.is_empty() && payouts.is_empty());
+    let (winners, _) = calculate_tier_payouts(&[7, 9], &[0, 0], &[10_000], true, 0).unwrap();
+    assert!(winners.is_empty());
+    println!("✅ Zero-bet market has no winner");
+}
+
+#[test]
+fn test_winner_all_bets_on_one_emoji() {
+    let payout_pool = 1_000_000_007u64;
+    let (winners, payouts) =
+        calculate_tier_payouts(&[42], &[30], &[7_000, 2_000, 1_000], false, payout_pool).unwrap();
+    assert_eq!(winners, vec![42]);
+    assert_eq!(payouts, vec![payout_pool]);
+
+    // Every bettor is a winner and together they take the whole payout pool
+    let user_votes = [10u64, 7, 13];
+    let total_votes: u64 = user_votes.iter().sum();
+    let paid: u64 = user_votes
+        .iter()
+        .map(|&v| calculate_user_share(payout_pool, v, total_votes).unwrap())
+        .sum();
+    assert!(paid <= payout_pool);
+    assert!(payout_pool - paid < user_votes.len() as u64);
+    println!("✅ Single-emoji market pays out the full pool");
+}
+
+#[test]
+fn test_winner_tie_break() {
+    let (winners, payouts) =
+        calculate_tier_payouts(&[5, 3, 8], &[4, 4, 2], &[10_000], false, 1_000).unwrap();
+    assert_eq!(winners, vec![3]);
+    assert_eq!(payouts, vec![1_000]);
+    let (winners, _) =
+        calculate_tier_payouts(&[5, 3, 8], &[4, 4, 9], &[10_000], false, 1_000).unwrap();
+    assert_eq!(winners, vec![8]);
+    println!("✅ Ties go to the lowest emoji_id");
+}
+
+#[test]
+fn test_winner_tie_split() {
+    // Each tied emoji gets an equal part of the pool, dust stays in the market
+    let (winners, payouts) =
+        calculate_tier_payouts(&[5, 3, 8], &[4, 4, 2], &[10_000], true, 1_001).unwrap();
+    assert_eq!(winners, vec![3, 5]);
+    assert_eq!(payouts, vec![500, 500]);
+    let (winners, _) =
+        calculate_tier_payouts(&[5, 3, 8], &[4, 4, 9], &[10_000], true, 1_001).unwrap();
+    assert_eq!(winners, vec![8]);
+    println!("✅ Tied emojis split the payout pool");
+}
+
+#[test]
+fn test_tiered_payouts() {
+    let schedule = [7_000u16, 2_000, 1_000];
+
+    // Top 3 paid 70/20/10, fourth place gets nothing
+    let (winners, payouts) =
+        calculate_tier_payouts(&[1, 2, 3, 4], &[10, 40, 30, 20], &schedule, false, 10_000)
+            .unwrap();
+    assert_eq!(winners, vec![2, 3, 4]);
+    assert_eq!(payouts, vec![7_000, 2_000, 1_000]);
+
+    // Two emojis tied for 1st share 1st + 2nd place weights, 3rd keeps its own
+    let (winners, payouts) =
+        calculate_tier_payouts(&[1, 2, 3], &[40, 40, 10], &schedule, true, 10_000).unwrap();
+    assert_eq!(winners, vec![1, 2, 3]);
+    assert_eq!(payouts, vec![4_500, 4_500, 1_000]);
+
+    // Without splitting, the lower id takes 1st place
+    let (winners, payouts) =
+        calculate_tier_payouts(&[2, 1, 3], &[40, 40, 10], &schedule, false, 10_000).unwrap();
+    assert_eq!(winners, vec![1, 2, 3]);
+    assert_eq!(payouts, vec![7_000, 2_000, 1_000]);
+
+    // Fewer placed emojis than tiers: used weights are scaled to the whole pool
+    let (winners, payouts) =
+        calculate_tier_payouts(&[1, 2], &[5, 3], &schedule, false, 9_000).unwrap();
+    assert_eq!(winners, vec![1, 2]);
+    assert_eq!(payouts, vec![7_000, 2_000]);
+
+    // Pre-populated choices nobody bet on never place
+    let (winners, payouts) =
+        calculate_tier_payouts(&[1, 2, 3], &[5, 0, 2], &schedule, false, 9_000).unwrap();
+    assert_eq!(winners, vec![1, 3]);
+    assert_eq!(payouts, vec![7_000, 2_000]);
+
+    println!("✅ Payout pool follows the tier schedule");
+}
+
+#[test]
+fn test_integral_pricing_matches_single_votes() {
+    let (base, malus, a, b) = (1_000_000u64, 250_000_000u64, 10_000u64, 1_000u64);
+
+    for &(n, qty) in &[(0u64, 1u64), (0, 10), (7, 100), (1_000, 10_000)] {
+        // One order for qty votes
+        let sum = calculate_quadratic_uplift_sum(n, qty, a, b).unwrap();
+        let bulk = calculate_total_cost(base, malus, sum).unwrap();
+
+        // qty single-vote orders at increasing vote counts
+        let split: u64 = (n..n + qty)
+            .map(|i| {
+                let f = calculate_quadratic_uplift(i, a, b).unwrap();
+                calculate_unit_price(base, malus, f).unwrap()
+            })
+            .sum();
+
+        // Rounding happens once instead of per vote, never in the buyer's favor
+        assert!(bulk >= split, "n={} qty={}: {} < {}", n, qty, bulk, split);
+        assert!(bulk - split <= qty, "n={} qty={}: {} vs {}", n, qty, bulk, split);
+    }
+
+    // A single vote costs exactly the spot unit price
+    let f = calculate_quadratic_uplift(42, a, b).unwrap();
+    let sum = calculate_quadratic_uplift_sum(42, 1, a, b).unwrap();
+    assert_eq!(sum, f as u128);
+    println!("✅ Large orders pay the whole quadratic curve");
+}
+
+#[test]
+fn test_sell_value_inverts_buy_without_malus() {
+    let (base, a, b) = (1_000_000u64, 10_000u64, 1_000u64);
+
+    for &(n, qty) in &[(0u64, 1u64), (0, 10), (7, 100), (1_000, 10_000)] {
+        // Selling the last qty votes returns what buying them cost with no malus
+        let sum = calculate_quadratic_uplift_sum(n, qty, a, b).unwrap();
+        let bought = calculate_total_cost(base, 0, sum).unwrap();
+        let sold = calculate_sell_value(base, n + qty, qty, a, b).unwrap();
+        assert_eq!(sold, bought, "n={} qty={}", n, qty);
+
+        // The time malus is never refunded
+        assert!(calculate_total_cost(base, 250_000_000, sum).unwrap() > sold);
+    }
+
+    // Can't sell more votes than the market holds
+    assert!(calculate_sell_value(base, 5, 6, a, b).is_err());
+    println!("✅ Sell value walks the curve back down");
+}
+
+#[test]
+fn test_exp_reference_values() {
+    // (x in nanos, floor((exp(x) - 1) * 1e9))
+    let cases: [(u64, u64); 8] = [
+        (0, 0),
+        (1_000_000, 1_000_500),
+        (500_000_000, 648_721_270),
+        (693_147_181, 1_000_000_000),
+        (1_000_000_000, 1_718_281_828),
+        (2_000_000_000, 6_389_056_098),
+        (5_000_000_000, 147_413_159_102),
+        (6_900_000_000, 991_274_715_605),
+    ];
+    for &(x, expected) in &cases {
+        let got = exp_minus_one(x).unwrap();
+        assert!(got.abs_diff(expected) <= 1, "exp({}): {} vs {}", x, got, expected);
+    }
+
+    // Saturates instead of overflowing
+    assert_eq!(exp_minus_one(6_910_000_000).unwrap(), MAX_MALUS_NANOS);
+    assert_eq!(exp_minus_one(50_000_000_000).unwrap(), MAX_MALUS_NANOS);
+    assert_eq!(exp_minus_one(u64::MAX).unwrap(), MAX_MALUS_NANOS);
+    println!("✅ Fixed-point exp matches reference values");
+}
+
+#[test]
+fn test_malus_over_time_range() {
+    let total = 1_000_000i64;
+    for &k_millis in &[100u32, 1_000, 2_500, 10_000] {
+        let mut previous = 0u64;
+        for elapsed in (0..total).step_by(9_973).chain([total - 100, total - 1]) {
+            let got = calculate_malus(elapsed, total, k_millis).unwrap();
+
+            let x = elapsed as f64 / total as f64;
+            let reference = ((k_millis as f64 / 1000.0) * x / (1.0 - x)).exp_m1() * 1e9;
+            let reference = reference.min(MAX_MALUS_NANOS as f64);
+            let tolerance = reference * 1e-6 + 2.0;
+            assert!(
+                (got as f64 - reference).abs() <= tolerance,
+                "k={} x={}: {} vs {}",
+                k_millis,
+                x,
+                got,
+                reference
+            );
+
+            // Price never drops as the market runs
+            assert!(got >= previous);
+            previous = got;
+        }
+
+        // At or past the end the malus is capped
+        assert_eq!(calculate_malus(total, total, k_millis).unwrap(), MAX_MALUS_NANOS);
+    }
+    println!("✅ Malus follows exp((k*x)/(1-x)) - 1 across [0, 1)");
+}
+
+#[test]
+fn test_emoji_registry_entry() {
+    assert_eq!(EmojiEntry::LEN, 8 + 4 + 4 + MAX_EMOJI_SEQUENCE_LEN + 1 + 1);
+
+    // ZWJ family sequence fits, empty and oversized ones don't
+    assert!(validate_sequence("👨‍👩‍👧‍👦").is_ok());
+    assert!(validate_sequence("").is_err());
+    assert!(validate_sequence(&"🔥".repeat(9)).is_err());
+
+    let entry = |emoji_id: u32, enabled: bool| {
+        let mut data = Vec::new();
+        EmojiEntry {
+            emoji_id,
+            sequence: "🔥".to_string(),
+            enabled,
+            bump: 255,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        data
+    };
+    let check = |mut data: Vec<u8>, owner: Pubkey, emoji_id: u32| {
+        let key = Pubkey::new_unique();
+        let mut lamports = 0u64;
+        let info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        require_registered(&info, emoji_id).is_ok()
+    };
+
+    assert!(check(entry(7, true), emojimarket_program::ID, 7));
+    assert!(!check(entry(7, true), emojimarket_program::ID, 8));
+    assert!(!check(entry(7, false), emojimarket_program::ID, 7));
+    assert!(!check(entry(7, true), Pubkey::new_unique(), 7));
+    println!("✅ Emoji ids are validated against the registry");
+}
+
 #[test]
 fn test_all_structures_complete() {
     println!("\n📊 Structure Summary:");
@@ -0,0 +1,155 @@
+// Processor tests for migrating a legacy Config to the current layout
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::system_program;
+use anchor_lang::{Discriminator, InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::{Config, Treasury};
+use solana_program_test::{processor, tokio, ProgramTest, ProgramTestContext};
+use solana_sdk::account::Account;
+use solana_sdk::signature::{Keypair, Signer};
+
+// discriminator + admin, fees, pricing, durations and bump
+const LEGACY_CONFIG_LEN: usize = 8 + 32 + 2 + 2 + 8 + 4 + 8 + 8 + 4 + 4 + 1;
+
+fn config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], &emojimarket_program::ID)
+}
+
+fn treasury_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury"], &emojimarket_program::ID).0
+}
+
+fn legacy_config_data(admin: &Pubkey, bump: u8) -> Vec<u8> {
+    let mut data = Config::DISCRIMINATOR.to_vec();
+    data.extend_from_slice(admin.as_ref());
+    data.extend_from_slice(&500u16.to_le_bytes()); // platform_fee_bps
+    data.extend_from_slice(&300u16.to_le_bytes()); // creator_fee_bps
+    data.extend_from_slice(&1_000_000u64.to_le_bytes()); // base_price_lamports
+    data.extend_from_slice(&1_000u32.to_le_bytes()); // malus_k_millis
+    data.extend_from_slice(&10_000u64.to_le_bytes()); // quad_a_micros
+    data.extend_from_slice(&1_000u64.to_le_bytes()); // quad_b_micros
+    data.extend_from_slice(&60u32.to_le_bytes()); // min_duration_secs
+    data.extend_from_slice(&86_400u32.to_le_bytes()); // max_duration_secs
+    data.push(bump);
+    assert_eq!(data.len(), LEGACY_CONFIG_LEN);
+    data
+}
+
+/// Program with only a legacy Config on chain and no Treasury
+async fn setup_legacy_config() -> (ProgramTestContext, Keypair) {
+    let admin = Keypair::new();
+    let (config, bump) = config_pda();
+    let mut program_test = ProgramTest::new(
+        "emojimarket_program",
+        emojimarket_program::ID,
+        processor!(process_instruction),
+    );
+    program_test.add_account(
+        config,
+        Account {
+            lamports: Rent::default().minimum_balance(LEGACY_CONFIG_LEN),
+            data: legacy_config_data(&admin.pubkey(), bump),
+            owner: emojimarket_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut context = program_test.start_with_context().await;
+    fund(&mut context, &admin.pubkey(), 10_000_000_000).await;
+    (context, admin)
+}
+
+fn migrate_config_ix(admin: Pubkey) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::MigrateConfig {
+            config: config_pda().0,
+            treasury: treasury_pda(),
+            admin,
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::MigrateConfig {
+            claim_window_secs: 604_800,
+            end_grace_secs: 86_400,
+            crank_fee_bps: 1_000,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_migrate_config_reallocs_and_creates_treasury() {
+    let (mut context, admin) = setup_legacy_config().await;
+
+    let ix = migrate_config_ix(admin.pubkey());
+    send(&mut context, ix, &[&admin]).await.unwrap();
+
+    let account = context
+        .banks_client
+        .get_account(config_pda().0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), Config::LEN);
+    assert!(account.lamports >= Rent::default().minimum_balance(Config::LEN));
+    let config = Config::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(config.admin_address, admin.pubkey());
+    assert_eq!(config.platform_fee_bps, 500);
+    assert_eq!(config.max_duration_secs, 86_400);
+    assert_eq!(config.claim_window_secs, 604_800);
+    assert_eq!(config.end_grace_secs, 86_400);
+    assert_eq!(config.crank_fee_bps, 1_000);
+    assert!(!config.markets_paused && !config.bets_paused && !config.withdrawals_paused);
+    assert_eq!(config.pending_admin, None);
+    assert_eq!(config.bump, config_pda().1);
+
+    let account = context
+        .banks_client
+        .get_account(treasury_pda())
+        .await
+        .unwrap()
+        .unwrap();
+    let treasury = Treasury::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(treasury.total_collected, 0);
+    println!("✅ Legacy config migrated and treasury created");
+}
+
+#[tokio::test]
+async fn test_migrate_config_is_admin_only() {
+    let (mut context, _admin) = setup_legacy_config().await;
+    let stranger = Keypair::new();
+    fund(&mut context, &stranger.pubkey(), 1_000_000_000).await;
+
+    let ix = migrate_config_ix(stranger.pubkey());
+    let result = send(&mut context, ix, &[&stranger]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+    println!("✅ Only the legacy admin can migrate");
+}
+
+#[tokio::test]
+async fn test_migrate_config_runs_once() {
+    let (mut context, admin) = setup_legacy_config().await;
+    let ix = migrate_config_ix(admin.pubkey());
+    send(&mut context, ix, &[&admin]).await.unwrap();
+    next_blockhash(&mut context).await;
+
+    let ix = migrate_config_ix(admin.pubkey());
+    let result = send(&mut context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::ConfigAlreadyMigrated);
+
+    // A config created in the current layout has nothing to migrate either
+    let mut setup = setup_open_market().await;
+    let admin = setup.admin.insecure_clone();
+    let ix = migrate_config_ix(admin.pubkey());
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::ConfigAlreadyMigrated);
+    println!("✅ Migration can't run on a current config");
+}
@@ -0,0 +1,95 @@
+// Processor tests for the admin pause switches
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::Market;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_set_pause_is_admin_only() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+
+    let ix = set_pause_ix(&setup, creator.pubkey(), true, true, true);
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+    println!("✅ Only the admin can pause");
+}
+
+#[tokio::test]
+async fn test_markets_pause_blocks_create_market() {
+    let mut setup = setup_open_market().await;
+    let creator = setup.creator.insecure_clone();
+    set_pause(&mut setup, true, false, false).await;
+
+    let ix = create_market_ix(&setup, MARKET_ID + 1);
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::MarketsPaused);
+
+    // Bets on existing markets are unaffected
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    println!("✅ Market creation pause only stops new markets");
+}
+
+#[tokio::test]
+async fn test_bets_pause_blocks_bet() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    set_pause(&mut setup, false, true, false).await;
+
+    let ix = bet_ix(&setup, 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::BetsPaused);
+
+    set_pause(&mut setup, false, false, false).await;
+    let ix = bet_ix(&setup, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    println!("✅ Betting pause can be lifted");
+}
+
+#[tokio::test]
+async fn test_withdrawals_pause_blocks_end_market() {
+    let mut setup = setup_ended_market().await;
+    let creator = setup.creator.insecure_clone();
+    set_pause(&mut setup, false, false, true).await;
+
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::WithdrawalsPaused);
+    println!("✅ Withdrawal pause stops end_market");
+}
+
+#[tokio::test]
+async fn test_withdrawals_pause_blocks_sweeping_unclaimed_funds() {
+    let mut setup = setup_ended_market().await;
+    let creator = setup.creator.insecure_clone();
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    // The pause outlasts the claim window while the winner can't claim
+    set_pause(&mut setup, false, false, true).await;
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    set_time(&mut setup.context, market.claim_deadline_ts).await;
+
+    let user = setup.user.pubkey();
+    let ix = close_bet_ix(&setup, user, creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::WithdrawalsPaused);
+    let ix = close_market_ix(&setup, creator.pubkey());
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::WithdrawalsPaused);
+    println!("✅ Unclaimed winnings can't be swept while withdrawals are paused");
+}
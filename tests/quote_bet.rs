@@ -0,0 +1,92 @@
+// Processor tests for quote_bet return data
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::instructions::BetQuote;
+use solana_program_test::tokio;
+use solana_sdk::{signature::Signer, transaction::Transaction};
+
+async fn simulate_quote(setup: &mut Setup, vote_qty: u64) -> BetQuote {
+    let ix = Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::QuoteBet {
+            market: setup.market,
+            emoji: emoji_pda(EMOJI_ID),
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::QuoteBet {
+            _market_id: MARKET_ID,
+            emoji_id: EMOJI_ID,
+            vote_qty,
+        }
+        .data(),
+    };
+    let payer = setup.context.payer.insecure_clone();
+    let blockhash = setup
+        .context
+        .banks_client
+        .get_latest_blockhash()
+        .await
+        .unwrap();
+    let tx = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], blockhash);
+    let simulation = setup
+        .context
+        .banks_client
+        .simulate_transaction(tx)
+        .await
+        .unwrap();
+    simulation.result.unwrap().unwrap();
+
+    let return_data = simulation.simulation_details.unwrap().return_data.unwrap();
+    assert_eq!(return_data.program_id, emojimarket_program::ID);
+    BetQuote::deserialize(&mut return_data.data.as_ref()).unwrap()
+}
+
+#[tokio::test]
+async fn test_quote_matches_charged_bet() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let market_key = setup.market;
+
+    // Move along the curve and the clock so the quote isn't trivial
+    let ix = bet_ix(&setup, 25);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts - DURATION_SECS / 2).await;
+
+    let quote = simulate_quote(&mut setup, 7).await;
+    assert_eq!(quote.emoji_id, EMOJI_ID);
+    assert_eq!(quote.vote_qty, 7);
+    assert!(quote.malus_nanos > 0);
+    // Later votes in the order sit higher on the curve than the spot price
+    assert!(quote.total_cost >= 7 * quote.unit_price);
+
+    // The same bet, capped at exactly the quoted cost, goes through
+    let before = setup
+        .context
+        .banks_client
+        .get_balance(market_key)
+        .await
+        .unwrap();
+    let mut ix = bet_ix(&setup, 7);
+    ix.data = emojimarket_program::instruction::Bet {
+        _market_id: MARKET_ID,
+        emoji_id: EMOJI_ID,
+        vote_qty: 7,
+        max_total_cost: quote.total_cost,
+    }
+    .data();
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let after = setup
+        .context
+        .banks_client
+        .get_balance(market_key)
+        .await
+        .unwrap();
+    assert_eq!(after - before, quote.total_cost);
+    println!("✅ quote_bet returns exactly what bet charges");
+}
@@ -0,0 +1,237 @@
+// Processor tests for selling votes back before the market ends
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::math::{calculate_fee, calculate_sell_value};
+use emojimarket_program::state::Market;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+/// What selling `vote_qty` votes at the market's current total pays out,
+/// `spent_sold` being the share of the bet's total_spent they carry
+fn expected_refund(market: &Market, vote_qty: u64, spent_sold: u64) -> u64 {
+    let value = calculate_sell_value(
+        market.base_price_lamports,
+        market.total_votes,
+        vote_qty,
+        market.quad_a_micros,
+        market.quad_b_micros,
+    )
+    .unwrap();
+    let refund =
+        value - calculate_fee(value, market.platform_fee_bps + market.creator_fee_bps).unwrap();
+    refund.min(spent_sold)
+}
+
+#[tokio::test]
+async fn test_sell_votes_refunds_curve_price_minus_spread() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let bet_key = setup.bet;
+    let market_key = setup.market;
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let before = market_state(&mut setup).await;
+    let spent_before = bet_state(&mut setup, bet_key).await.total_spent;
+    let lamports_before = setup
+        .context
+        .banks_client
+        .get_balance(market_key)
+        .await
+        .unwrap();
+    let refund = expected_refund(&before, 4, spent_before * 4 / 10);
+    assert!(refund > 0);
+
+    let ix = sell_votes_ix(&setup, 4, refund);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let market = market_state(&mut setup).await;
+    let bet = bet_state(&mut setup, bet_key).await;
+    let lamports_after = setup
+        .context
+        .banks_client
+        .get_balance(market_key)
+        .await
+        .unwrap();
+    assert_eq!(lamports_before - lamports_after, refund);
+    assert_eq!(market.total_pot, before.total_pot - refund);
+    assert_eq!(market.total_votes, 6);
+    assert_eq!(market.emoji_votes, vec![6]);
+    assert_eq!(bet.emoji_votes, vec![6]);
+    assert_eq!(bet.total_spent, spent_before - spent_before * 4 / 10);
+    println!("✅ Sold votes are refunded at the curve price minus the spread");
+}
+
+#[tokio::test]
+async fn test_sell_capped_at_cost_keeps_refunds_whole() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let bet_key = setup.bet;
+    let other = add_bettor(&mut setup).await;
+    let market_key = setup.market;
+
+    // Steepen the market's curve to f(n) = 1 + 0.01n so the sale would make a profit
+    let mut account = setup
+        .context
+        .banks_client
+        .get_account(market_key)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    market.quad_a_micros = 10_000_000_000;
+    market.quad_b_micros = 0;
+    market
+        .try_serialize(&mut account.data.as_mut_slice())
+        .unwrap();
+    setup.context.set_account(&market_key, &account.into());
+
+    // A buys low, B buys higher up the curve, A sells near the top
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = bet_ix_for(&setup, other.pubkey(), 10);
+    send(&mut setup.context, ix, &[&other]).await.unwrap();
+
+    let before = market_state(&mut setup).await;
+    let spent_sold = bet_state(&mut setup, bet_key).await.total_spent * 6 / 10;
+    // The curve alone would pay A more than those votes cost
+    let value = calculate_sell_value(
+        before.base_price_lamports,
+        before.total_votes,
+        6,
+        before.quad_a_micros,
+        before.quad_b_micros,
+    )
+    .unwrap();
+    assert!(value - calculate_fee(value, 800).unwrap() > spent_sold);
+
+    let user_before = setup
+        .context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    let ix = sell_votes_ix(&setup, 6, 0);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let user_after = setup
+        .context
+        .banks_client
+        .get_balance(user.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(user_after - user_before, spent_sold);
+
+    let market = market_state(&mut setup).await;
+    let spent_a = bet_state(&mut setup, bet_key).await.total_spent;
+    let other_bet = bet_pda(&market_key, &other.pubkey());
+    let spent_b = bet_state(&mut setup, other_bet).await.total_spent;
+    assert!(market.total_pot >= spent_a + spent_b);
+
+    let admin = setup.admin.insecure_clone();
+    let ix = cancel_market_ix(&setup, admin.pubkey());
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+
+    // Both bettors get back everything their position still carries
+    for (bettor, spent) in [(&user, spent_a), (&other, spent_b)] {
+        let before = setup
+            .context
+            .banks_client
+            .get_balance(bettor.pubkey())
+            .await
+            .unwrap();
+        let ix = refund_ix(&setup, bettor.pubkey());
+        send(&mut setup.context, ix, &[bettor]).await.unwrap();
+        let after = setup
+            .context
+            .banks_client
+            .get_balance(bettor.pubkey())
+            .await
+            .unwrap();
+        assert_eq!(after - before, spent);
+    }
+
+    let account = setup
+        .context
+        .banks_client
+        .get_account(market_key)
+        .await
+        .unwrap()
+        .unwrap();
+    let rent = setup.context.banks_client.get_rent().await.unwrap();
+    assert!(account.lamports >= rent.minimum_balance(account.data.len()));
+    println!("✅ Sells are capped at cost so refunds stay whole");
+}
+
+#[tokio::test]
+async fn test_sell_votes_rejects_slippage_and_oversell() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let bet_key = setup.bet;
+    let ix = bet_ix(&setup, 5);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let market = market_state(&mut setup).await;
+    let spent = bet_state(&mut setup, bet_key).await.total_spent;
+    let refund = expected_refund(&market, 2, spent * 2 / 5);
+    let ix = sell_votes_ix(&setup, 2, refund + 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::SlippageExceeded);
+
+    let ix = sell_votes_ix(&setup, 6, 0);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::InsufficientVotes);
+    println!("✅ Sells respect min_refund and the user's position");
+}
+
+#[tokio::test]
+async fn test_sell_votes_rejected_after_end() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 5);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = sell_votes_ix(&setup, 1, 0);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::MarketEnded);
+    println!("✅ Votes can't be sold once the market is over");
+}
+
+#[tokio::test]
+async fn test_sell_votes_on_token_market() {
+    let mut setup = setup_open_token_market().await;
+    let user = setup.user.insecure_clone();
+    let bet_key = setup.bet;
+    let market_key = setup.market;
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let before = market_state(&mut setup).await;
+    let user_before = token_balance(&mut setup, &user.pubkey()).await;
+    let spent = bet_state(&mut setup, bet_key).await.total_spent;
+    let refund = expected_refund(&before, 10, spent);
+
+    let ix = sell_votes_ix(&setup, 10, refund);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    // The spread stays in the vault and the position is emptied
+    let market = market_state(&mut setup).await;
+    let bet = bet_state(&mut setup, bet_key).await;
+    assert_eq!(
+        token_balance(&mut setup, &user.pubkey()).await - user_before,
+        refund
+    );
+    assert_eq!(
+        token_balance(&mut setup, &market_key).await,
+        market.total_pot
+    );
+    assert_eq!(market.total_pot, before.total_pot - refund);
+    assert_eq!(market.total_votes, 0);
+    assert_eq!(bet.total_spent, 0);
+    println!("✅ Token markets refund sold votes from the vault");
+}
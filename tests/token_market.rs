@@ -0,0 +1,171 @@
+// Processor tests for SPL token denominated markets
+
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::BetAccount;
+use solana_program_test::tokio;
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn test_token_market_bet_end_and_claim() {
+    let mut setup = setup_open_token_market().await;
+    let user = setup.user.insecure_clone();
+    let creator = setup.creator.insecure_clone();
+    let market_key = setup.market;
+    let treasury_key = setup.treasury;
+
+    let market = market_state(&mut setup).await;
+    assert_eq!(market.mint, setup.mint);
+    assert_eq!(market.base_price_lamports, TOKEN_BASE_PRICE);
+
+    // Votes are paid in tokens into the market's vault
+    let user_before = token_balance(&mut setup, &user.pubkey()).await;
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let market = market_state(&mut setup).await;
+    assert_eq!(
+        token_balance(&mut setup, &market_key).await,
+        market.total_pot
+    );
+    assert_eq!(
+        user_before - token_balance(&mut setup, &user.pubkey()).await,
+        market.total_pot
+    );
+
+    // Fees go to the treasury's and creator's token accounts
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    let market = market_state(&mut setup).await;
+    assert_eq!(
+        token_balance(&mut setup, &treasury_key).await,
+        market.platform_fee_taken
+    );
+    assert_eq!(
+        token_balance(&mut setup, &creator.pubkey()).await,
+        market.creator_fee_taken
+    );
+
+    // The only bettor takes the whole payout pool
+    let ix = claim_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    assert_eq!(token_balance(&mut setup, &market_key).await, 0);
+    assert_eq!(
+        token_balance(&mut setup, &user.pubkey()).await,
+        user_before - market.platform_fee_taken - market.creator_fee_taken
+    );
+    println!("✅ Token markets move bets, fees and claims through the vault");
+}
+
+#[tokio::test]
+async fn test_token_market_rejects_foreign_token_account() {
+    let mut setup = setup_open_token_market().await;
+    let user = setup.user.insecure_clone();
+
+    // Paying from someone else's token account is refused
+    let mut ix = bet_ix(&setup, 1);
+    let creator_tokens = token_account(&setup, &setup.creator.pubkey());
+    let user_tokens = token_account(&setup, &user.pubkey());
+    for meta in ix.accounts.iter_mut() {
+        if meta.pubkey == user_tokens {
+            meta.pubkey = creator_tokens;
+        }
+    }
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::InvalidTokenAccount);
+    println!("✅ Token accounts must belong to the signer");
+}
+
+#[tokio::test]
+async fn test_sol_bet_on_token_market_is_rejected() {
+    let mut setup = setup_open_token_market().await;
+    let user = setup.user.insecure_clone();
+
+    // A token market never falls back to SOL
+    let mint = setup.mint.take();
+    let ix = bet_ix(&setup, 1);
+    setup.mint = mint;
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::MissingTokenAccounts);
+    println!("✅ Token markets require their token accounts");
+}
+
+#[tokio::test]
+async fn test_token_2022_transfer_fee_credits_received_amount() {
+    // 1% transfer fee on every move of the token
+    let mut setup = setup_open_token_2022_market(100).await;
+    let user = setup.user.insecure_clone();
+    let creator = setup.creator.insecure_clone();
+    let market_key = setup.market;
+
+    let user_before = token_balance(&mut setup, &user.pubkey()).await;
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    // The pot and the bet only count what reached the vault
+    let market = market_state(&mut setup).await;
+    let paid = user_before - token_balance(&mut setup, &user.pubkey()).await;
+    let vault_balance = token_balance(&mut setup, &market_key).await;
+    assert_eq!(market.total_pot, vault_balance);
+    assert_eq!(vault_balance, paid - paid / 100);
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.bet)
+        .await
+        .unwrap()
+        .unwrap();
+    let bet = BetAccount::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(bet.total_spent, vault_balance);
+
+    // Fees and the claim drain the vault exactly, nobody is short-changed
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    let ix = claim_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    assert_eq!(token_balance(&mut setup, &market_key).await, 0);
+    println!("✅ Transfer-fee mints credit the amount actually received");
+}
+
+#[tokio::test]
+async fn test_token_2022_market_closes_with_withheld_fees() {
+    let mut setup = setup_open_token_2022_market(100).await;
+    let user = setup.user.insecure_clone();
+    let creator = setup.creator.insecure_clone();
+    let market_key = setup.market;
+    let vault = token_account(&setup, &market_key);
+
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    let ix = claim_ix(&setup, user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = close_bet_ix(&setup, user.pubkey(), user.pubkey());
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    // The bet's transfer fee is still withheld in the vault
+    let deadline = market_state(&mut setup).await.claim_deadline_ts;
+    set_time(&mut setup.context, deadline).await;
+    let ix = close_market_ix(&setup, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+
+    for closed in [market_key, vault] {
+        assert!(setup
+            .context
+            .banks_client
+            .get_account(closed)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    println!("✅ Withheld transfer fees are harvested before the vault closes");
+}
@@ -0,0 +1,160 @@
+// Processor tests for moving votes between wallets
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, system_program, sysvar};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::Market;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+fn transfer_position_ix(setup: &Setup, to: Pubkey, vote_qty: u64) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::TransferPosition {
+            config: setup.config,
+            market: setup.market,
+            bet: setup.bet,
+            to,
+            to_bet: bet_pda(&setup.market, &to),
+            user: setup.user.pubkey(),
+            system_program: system_program::ID,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::TransferPosition {
+            _market_id: MARKET_ID,
+            emoji_id: EMOJI_ID,
+            vote_qty,
+        }
+        .data(),
+    }
+}
+
+#[tokio::test]
+async fn test_transfer_position_moves_votes_and_cost_basis() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let recipient = Keypair::new().pubkey();
+    let from_bet = setup.bet;
+    let ix = bet_ix(&setup, 10);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let spent_before = bet_state(&mut setup, from_bet).await.total_spent;
+
+    let ix = transfer_position_ix(&setup, recipient, 4);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let from = bet_state(&mut setup, from_bet).await;
+    let to_bet = bet_pda(&setup.market, &recipient);
+    let to = bet_state(&mut setup, to_bet).await;
+    assert_eq!(from.emoji_votes, vec![6]);
+    assert_eq!(to.user, recipient);
+    assert_eq!(to.emoji_ids, vec![EMOJI_ID]);
+    assert_eq!(to.emoji_votes, vec![4]);
+    assert_eq!(to.total_spent, spent_before * 4 / 10);
+    assert_eq!(from.total_spent + to.total_spent, spent_before);
+
+    // The market's totals don't move, but it tracks the new BetAccount
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    assert_eq!(market.total_votes, 10);
+    assert_eq!(market.open_bets, 2);
+    println!("✅ Votes and their cost basis move to the recipient");
+}
+
+#[tokio::test]
+async fn test_transfer_position_rejects_self_and_oversized_transfers() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 3);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let ix = transfer_position_ix(&setup, user.pubkey(), 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::SelfTransfer);
+
+    let ix = transfer_position_ix(&setup, Keypair::new().pubkey(), 4);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::InsufficientVotes);
+    println!("✅ Transfers need another wallet and enough votes");
+}
+
+#[tokio::test]
+async fn test_transfer_position_rejected_after_end() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 3);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = transfer_position_ix(&setup, Keypair::new().pubkey(), 1);
+    let result = send(&mut setup.context, ix, &[&user]).await;
+    assert_custom_error(result, ErrorCode::MarketEnded);
+    println!("✅ Positions are frozen once the market is over");
+}
+
+#[tokio::test]
+async fn test_transfer_position_requires_system_owned_recipient() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let ix = bet_ix(&setup, 3);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    // Accounts that could never be written to would keep the bet open forever
+    for to in [
+        Pubkey::default(),
+        sysvar::clock::ID,
+        emojimarket_program::ID,
+    ] {
+        let ix = transfer_position_ix(&setup, to, 1);
+        let result = send(&mut setup.context, ix, &[&user]).await;
+        assert!(result.is_err(), "transfer to {} went through", to);
+    }
+    println!("✅ Positions only move to wallets");
+}
+
+#[tokio::test]
+async fn test_transferred_position_closes_with_market() {
+    let mut setup = setup_open_market().await;
+    let user = setup.user.insecure_clone();
+    let creator = setup.creator.insecure_clone();
+    let recipient = Keypair::new().pubkey();
+    let ix = bet_ix(&setup, 3);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+    let ix = transfer_position_ix(&setup, recipient, 1);
+    send(&mut setup.context, ix, &[&user]).await.unwrap();
+
+    let end_ts = setup.end_ts;
+    set_time(&mut setup.context, end_ts).await;
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.market)
+        .await
+        .unwrap()
+        .unwrap();
+    let market = Market::try_deserialize(&mut account.data.as_ref()).unwrap();
+    set_time(&mut setup.context, market.claim_deadline_ts).await;
+
+    // Anyone can close both bets once the window is over, then the market
+    for owner in [user.pubkey(), recipient] {
+        let ix = close_bet_ix(&setup, owner, creator.pubkey());
+        send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    }
+    let ix = close_market_ix(&setup, creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    println!("✅ A transferred position doesn't block closing the market");
+}
@@ -0,0 +1,138 @@
+// Processor tests for treasury withdrawals
+
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use common::*;
+use emojimarket_program::error::ErrorCode;
+use emojimarket_program::state::Treasury;
+use solana_program_test::tokio;
+use solana_sdk::signature::{Keypair, Signer};
+
+/// Ended market whose platform fee has been collected into the treasury
+async fn setup_funded_treasury() -> Setup {
+    let mut setup = setup_ended_market().await;
+    let creator = setup.creator.insecure_clone();
+    let ix = end_market_ix(&setup, creator.pubkey(), creator.pubkey());
+    send(&mut setup.context, ix, &[&creator]).await.unwrap();
+    setup
+}
+
+fn withdraw_treasury_ix(
+    setup: &Setup,
+    admin: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id: emojimarket_program::ID,
+        accounts: emojimarket_program::accounts::WithdrawTreasury {
+            config: setup.config,
+            treasury: setup.treasury,
+            admin,
+            destination,
+            mint: None,
+            treasury_token_account: None,
+            destination_token_account: None,
+            token_program: None,
+            event_authority: event_authority(),
+            program: emojimarket_program::ID,
+        }
+        .to_account_metas(None),
+        data: emojimarket_program::instruction::WithdrawTreasury { amount }.data(),
+    }
+}
+
+async fn treasury_state(setup: &mut Setup) -> (u64, Treasury) {
+    let account = setup
+        .context
+        .banks_client
+        .get_account(setup.treasury)
+        .await
+        .unwrap()
+        .unwrap();
+    let treasury = Treasury::try_deserialize(&mut account.data.as_ref()).unwrap();
+    (account.lamports, treasury)
+}
+
+#[tokio::test]
+async fn test_withdraw_treasury_partial() {
+    let mut setup = setup_funded_treasury().await;
+    let admin = setup.admin.insecure_clone();
+    let destination = Keypair::new().pubkey();
+    fund(&mut setup.context, &destination, 1_000_000_000).await;
+
+    let (lamports_before, treasury) = treasury_state(&mut setup).await;
+    let amount = treasury.total_collected / 2;
+    assert!(amount > 0);
+
+    let ix = withdraw_treasury_ix(&setup, admin.pubkey(), destination, amount);
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+
+    let (lamports_after, treasury) = treasury_state(&mut setup).await;
+    let received = setup
+        .context
+        .banks_client
+        .get_balance(destination)
+        .await
+        .unwrap();
+    assert_eq!(lamports_before - lamports_after, amount);
+    assert_eq!(received, 1_000_000_000 + amount);
+    assert_eq!(treasury.total_withdrawn, amount);
+    println!("✅ Admin can withdraw part of the treasury");
+}
+
+#[tokio::test]
+async fn test_withdraw_treasury_rejects_non_admin() {
+    let mut setup = setup_funded_treasury().await;
+    let creator = setup.creator.insecure_clone();
+
+    let ix = withdraw_treasury_ix(&setup, creator.pubkey(), creator.pubkey(), 1);
+    let result = send(&mut setup.context, ix, &[&creator]).await;
+    assert_custom_error(result, ErrorCode::Unauthorized);
+    println!("✅ Only the admin can withdraw from the treasury");
+}
+
+#[tokio::test]
+async fn test_withdraw_treasury_keeps_rent() {
+    let mut setup = setup_funded_treasury().await;
+    let admin = setup.admin.insecure_clone();
+
+    // Everything collected can leave, but not the rent reserve on top of it
+    let (_, treasury) = treasury_state(&mut setup).await;
+    let ix = withdraw_treasury_ix(
+        &setup,
+        admin.pubkey(),
+        admin.pubkey(),
+        treasury.total_collected + 1,
+    );
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::InsufficientTreasuryFunds);
+
+    let ix = withdraw_treasury_ix(&setup, admin.pubkey(), admin.pubkey(), 0);
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::InvalidWithdrawAmount);
+
+    let ix = withdraw_treasury_ix(
+        &setup,
+        admin.pubkey(),
+        admin.pubkey(),
+        treasury.total_collected,
+    );
+    send(&mut setup.context, ix, &[&admin]).await.unwrap();
+    println!("✅ Treasury withdrawals are bounded by collected fees");
+}
+
+#[tokio::test]
+async fn test_withdraw_treasury_blocked_by_withdrawals_pause() {
+    let mut setup = setup_funded_treasury().await;
+    let admin = setup.admin.insecure_clone();
+    set_pause(&mut setup, false, false, true).await;
+
+    let ix = withdraw_treasury_ix(&setup, admin.pubkey(), admin.pubkey(), 1);
+    let result = send(&mut setup.context, ix, &[&admin]).await;
+    assert_custom_error(result, ErrorCode::WithdrawalsPaused);
+    println!("✅ Treasury withdrawals wait for the pause to lift");
+}